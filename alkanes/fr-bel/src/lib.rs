@@ -0,0 +1,33 @@
+//! Synthetic Bellscoin (frBEL) Contract
+//!
+//! A synthetic representation of Bellscoin on the Subfrost protocol.
+//! It allows users to wrap their BEL into frBEL and unwrap frBEL back to BEL.
+//! The signer holds BEL in P2PKH outputs, the script every Dogecoin-family
+//! chain relays, and unwraps pay at least the shared Dogecoin-family dust
+//! limit, see [`DOGECOIN_DUST_LIMIT`].
+
+use bitcoin::secp256k1::XOnlyPublicKey;
+use bitcoin::{Script, ScriptBuf};
+use synthetic_asset::{
+    declare_synthetic_asset, p2pkh_lock_script, Network, SyntheticAsset, DOGECOIN_DUST_LIMIT,
+};
+
+#[derive(Default)]
+pub struct SyntheticBellscoin(());
+
+impl SyntheticAsset for SyntheticBellscoin {
+    const NAME: &'static str = "SUBFROST BEL";
+    const SYMBOL: &'static str = "frBEL";
+    const DECIMALS: u8 = 8; // Same as BEL
+    const NETWORKS: &'static [Network] = &[Network::Regtest, Network::Bellscoin];
+
+    fn dust_limit(&self, _script: &Script) -> u64 {
+        DOGECOIN_DUST_LIMIT
+    }
+
+    fn lock_script(&self, key: XOnlyPublicKey) -> ScriptBuf {
+        p2pkh_lock_script(key)
+    }
+}
+
+declare_synthetic_asset!(SyntheticBellscoin, SyntheticBellscoinMessage);
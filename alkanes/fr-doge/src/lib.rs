@@ -0,0 +1,32 @@
+//! Synthetic Dogecoin (frDOGE) Contract
+//!
+//! A synthetic representation of Dogecoin on the Subfrost protocol.
+//! It allows users to wrap their DOGE into frDOGE and unwrap frDOGE back to DOGE.
+//! Dogecoin has neither segwit nor taproot, so the signer holds DOGE in P2PKH
+//! outputs and unwraps pay at least Dogecoin Core's 0.01 DOGE dust limit.
+
+use bitcoin::secp256k1::XOnlyPublicKey;
+use bitcoin::{Script, ScriptBuf};
+use synthetic_asset::{
+    declare_synthetic_asset, p2pkh_lock_script, Network, SyntheticAsset, DOGECOIN_DUST_LIMIT,
+};
+
+#[derive(Default)]
+pub struct SyntheticDogecoin(());
+
+impl SyntheticAsset for SyntheticDogecoin {
+    const NAME: &'static str = "SUBFROST DOGE";
+    const SYMBOL: &'static str = "frDOGE";
+    const DECIMALS: u8 = 8; // Same as DOGE
+    const NETWORKS: &'static [Network] = &[Network::Regtest, Network::Dogecoin];
+
+    fn dust_limit(&self, _script: &Script) -> u64 {
+        DOGECOIN_DUST_LIMIT
+    }
+
+    fn lock_script(&self, key: XOnlyPublicKey) -> ScriptBuf {
+        p2pkh_lock_script(key)
+    }
+}
+
+declare_synthetic_asset!(SyntheticDogecoin, SyntheticDogecoinMessage);
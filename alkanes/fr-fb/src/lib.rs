@@ -0,0 +1,20 @@
+//! Synthetic Fractal Bitcoin (frFB) Contract
+//!
+//! A synthetic representation of Fractal Bitcoin on the Subfrost protocol.
+//! It allows users to wrap their FB into frFB and unwrap frFB back to FB.
+//! Fractal Bitcoin shares Bitcoin's script and dust rules, so only the metadata differs
+//! from frBTC.
+
+use synthetic_asset::{declare_synthetic_asset, Network, SyntheticAsset};
+
+#[derive(Default)]
+pub struct SyntheticFractal(());
+
+impl SyntheticAsset for SyntheticFractal {
+    const NAME: &'static str = "SUBFROST FB";
+    const SYMBOL: &'static str = "frFB";
+    const DECIMALS: u8 = 8; // Same as FB
+    const NETWORKS: &'static [Network] = &[Network::Regtest, Network::Fractal];
+}
+
+declare_synthetic_asset!(SyntheticFractal, SyntheticFractalMessage);
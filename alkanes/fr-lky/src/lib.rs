@@ -0,0 +1,33 @@
+//! Synthetic Luckycoin (frLKY) Contract
+//!
+//! A synthetic representation of Luckycoin on the Subfrost protocol.
+//! It allows users to wrap their LKY into frLKY and unwrap frLKY back to LKY.
+//! The signer holds LKY in P2PKH outputs, the script every Dogecoin-family
+//! chain relays, and unwraps pay at least the shared Dogecoin-family dust
+//! limit, see [`DOGECOIN_DUST_LIMIT`].
+
+use bitcoin::secp256k1::XOnlyPublicKey;
+use bitcoin::{Script, ScriptBuf};
+use synthetic_asset::{
+    declare_synthetic_asset, p2pkh_lock_script, Network, SyntheticAsset, DOGECOIN_DUST_LIMIT,
+};
+
+#[derive(Default)]
+pub struct SyntheticLuckycoin(());
+
+impl SyntheticAsset for SyntheticLuckycoin {
+    const NAME: &'static str = "SUBFROST LKY";
+    const SYMBOL: &'static str = "frLKY";
+    const DECIMALS: u8 = 8; // Same as LKY
+    const NETWORKS: &'static [Network] = &[Network::Regtest, Network::Luckycoin];
+
+    fn dust_limit(&self, _script: &Script) -> u64 {
+        DOGECOIN_DUST_LIMIT
+    }
+
+    fn lock_script(&self, key: XOnlyPublicKey) -> ScriptBuf {
+        p2pkh_lock_script(key)
+    }
+}
+
+declare_synthetic_asset!(SyntheticLuckycoin, SyntheticLuckycoinMessage);
@@ -1,7 +1,8 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use flate2::write::GzEncoder;
 use flate2::Compression;
 use hex;
+use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::io::prelude::*;
@@ -14,6 +15,52 @@ fn compress(binary: Vec<u8>) -> Result<Vec<u8>> {
     Ok(writer.finish()?)
 }
 
+/// Fail if two message variants of an alkane share an opcode, which would
+/// leave all but the first unreachable
+fn check_unique_opcodes(source: &Path) -> Result<()> {
+    let text = fs::read_to_string(source)?;
+    let mut seen: HashMap<u128, String> = HashMap::new();
+    let mut pending: Option<u128> = None;
+    for line in text.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("#[opcode(") {
+            pending = Some(rest.trim_end_matches(")]").trim().parse::<u128>()?);
+        } else if let Some(opcode) = pending {
+            if line.is_empty() || line.starts_with("#[") || line.starts_with("//") {
+                continue;
+            }
+            pending = None;
+            let variant = line
+                .split(|c: char| !c.is_alphanumeric() && c != '_')
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            if let Some(previous) = seen.insert(opcode, variant.clone()) {
+                return Err(anyhow!(
+                    "{}: opcode {} is assigned to both {} and {}",
+                    source.display(),
+                    opcode,
+                    previous,
+                    variant
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Get the features declared in the `[features]` table of a manifest
+fn declared_features(manifest: &Path) -> Result<Vec<String>> {
+    let text = fs::read_to_string(manifest)?;
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| *line != "[features]")
+        .skip(1)
+        .take_while(|line| !line.starts_with('['))
+        .filter_map(|line| line.split_once('=').map(|(name, _)| name.trim().to_string()))
+        .collect())
+}
+
 fn build_alkane(wasm_str: &str, features: Vec<String>) -> Result<()> {
     if !features.is_empty() {
         let _ = Command::new("cargo")
@@ -42,6 +89,7 @@ fn build_alkane(wasm_str: &str, features: Vec<String>) -> Result<()> {
 
 fn main() {
     println!("cargo:rerun-if-changed=alkanes/");
+    println!("cargo:rerun-if-changed=crates/synthetic-asset/");
     let env_var = env::var_os("OUT_DIR").unwrap();
     let base_dir = Path::new(&env_var)
         .parent()
@@ -75,6 +123,17 @@ fn main() {
         .parent()
         .unwrap()
         .join("alkanes");
+    // The synthetic assets share the message enum declared by the library
+    check_unique_opcodes(
+        &crates_dir
+            .parent()
+            .unwrap()
+            .join("crates")
+            .join("synthetic-asset")
+            .join("src")
+            .join("lib.rs"),
+    )
+    .unwrap();
     std::env::set_current_dir(&crates_dir).unwrap();
     let mods = fs::read_dir(&crates_dir)
         .unwrap()
@@ -97,8 +156,19 @@ fn main() {
     files
         .into_iter()
         .map(|v| -> Result<String> {
+            check_unique_opcodes(&crates_dir.join(&v).join("src").join("lib.rs"))?;
             std::env::set_current_dir(&crates_dir.clone().join(v.clone()))?;
-            build_alkane(wasm_str, features.clone())?;
+            // Only pass the features the alkane declares, so a network
+            // feature reaches only the assets deployable to that network
+            let declared = declared_features(&crates_dir.join(&v).join("Cargo.toml"))?;
+            build_alkane(
+                wasm_str,
+                features
+                    .iter()
+                    .filter(|feature| declared.contains(feature))
+                    .cloned()
+                    .collect(),
+            )?;
             std::env::set_current_dir(&crates_dir)?;
             let subbed = v.clone().replace("-", "_");
             eprintln!(
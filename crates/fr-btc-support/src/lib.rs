@@ -1,15 +1,218 @@
 //! Shared constants and types for the frBTC alkane.
 
-/// Default signer pubkey for testnet
-#[cfg(feature = "testnet")]
-pub const DEFAULT_SIGNER_PUBKEY: [u8; 32] = [
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use bitcoin::key::TapTweak;
+use bitcoin::secp256k1::{self, Message, Scalar, Secp256k1, XOnlyPublicKey};
+use bitcoin::{OutPoint, Script, ScriptBuf};
+
+/// Networks frBTC can be deployed to, identified by their discriminant
+///
+/// No network is 0, so a missing or zeroed argument never selects one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Network {
+    Regtest = 1,
+    Testnet = 2,
+    Signet = 3,
+    Mainnet = 4,
+    Fractal = 5,
+    Dogecoin = 6,
+    Luckycoin = 7,
+    Bellscoin = 8,
+}
+
+impl TryFrom<u128> for Network {
+    type Error = anyhow::Error;
+
+    fn try_from(v: u128) -> anyhow::Result<Self> {
+        Network::ALL
+            .into_iter()
+            .find(|network| *network as u128 == v)
+            .ok_or_else(|| anyhow::anyhow!("unknown network {}", v))
+    }
+}
+
+/// Signer group key of the SUBFROST federation
+const SUBFROST_SIGNER_PUBKEY: [u8; 32] = [
     0x07, 0x9a, 0x54, 0xd0, 0xae, 0xf2, 0xb3, 0x43, 0xaa, 0xc8, 0x9c, 0x0f, 0xd7, 0x89, 0xaa, 0xb4,
     0xac, 0xb9, 0x1f, 0x00, 0xca, 0xa0, 0xf8, 0xd5, 0x15, 0x01, 0x45, 0x2c, 0xe4, 0x7c, 0xc9, 0x7d,
 ];
 
-/// Default signer pubkey for all other networks (zeros)
-#[cfg(not(feature = "testnet"))]
-pub const DEFAULT_SIGNER_PUBKEY: [u8; 32] = [
-    0x07, 0x9a, 0x54, 0xd0, 0xae, 0xf2, 0xb3, 0x43, 0xaa, 0xc8, 0x9c, 0x0f, 0xd7, 0x89, 0xaa, 0xb4,
-    0xac, 0xb9, 0x1f, 0x00, 0xca, 0xa0, 0xf8, 0xd5, 0x15, 0x01, 0x45, 0x2c, 0xe4, 0x7c, 0xc9, 0x7d,
-];
\ No newline at end of file
+impl Network {
+    pub const ALL: [Network; 8] = [
+        Network::Regtest,
+        Network::Testnet,
+        Network::Signet,
+        Network::Mainnet,
+        Network::Fractal,
+        Network::Dogecoin,
+        Network::Luckycoin,
+        Network::Bellscoin,
+    ];
+
+    /// Get the x-only pubkey the signer defaults to before one is set
+    ///
+    /// This is the per-network default signer table. Only mainnet has a
+    /// published federation key. No key has been published for the other
+    /// networks, and a compiled-in regtest key would have a public secret,
+    /// so their signer must be set with `SetSigner` before wraps are
+    /// accepted.
+    pub const fn default_signer_pubkey(self) -> Option<[u8; 32]> {
+        match self {
+            Network::Mainnet => Some(SUBFROST_SIGNER_PUBKEY),
+            Network::Regtest
+            | Network::Testnet
+            | Network::Signet
+            | Network::Fractal
+            | Network::Dogecoin
+            | Network::Luckycoin
+            | Network::Bellscoin => None,
+        }
+    }
+
+    /// Get the default signer as a typed key
+    pub fn default_signer(self) -> Option<XOnlyPublicKey> {
+        self.default_signer_pubkey().map(|pubkey| {
+            XOnlyPublicKey::from_slice(&pubkey).expect("default signer pubkeys are valid x-only keys")
+        })
+    }
+}
+
+/// The network selected by the build features, regtest by default
+#[cfg(not(any(
+    feature = "mainnet",
+    feature = "testnet",
+    feature = "luckycoin",
+    feature = "dogecoin",
+    feature = "bellscoin",
+    feature = "fractal",
+    feature = "signet"
+)))]
+pub const CONFIGURED_NETWORK: Network = Network::Regtest;
+#[cfg(feature = "mainnet")]
+pub const CONFIGURED_NETWORK: Network = Network::Mainnet;
+#[cfg(feature = "testnet")]
+pub const CONFIGURED_NETWORK: Network = Network::Testnet;
+#[cfg(feature = "luckycoin")]
+pub const CONFIGURED_NETWORK: Network = Network::Luckycoin;
+#[cfg(feature = "dogecoin")]
+pub const CONFIGURED_NETWORK: Network = Network::Dogecoin;
+#[cfg(feature = "bellscoin")]
+pub const CONFIGURED_NETWORK: Network = Network::Bellscoin;
+#[cfg(feature = "fractal")]
+pub const CONFIGURED_NETWORK: Network = Network::Fractal;
+#[cfg(feature = "signet")]
+pub const CONFIGURED_NETWORK: Network = Network::Signet;
+
+/// Get the default signer for the configured network, if it has one
+pub fn default_signer() -> Option<XOnlyPublicKey> {
+    CONFIGURED_NETWORK.default_signer()
+}
+
+/// Compute the BIP-340 tagged hash of the concatenated `data` under `tag`
+fn tagged_hash(tag: &[u8], data: &[&[u8]]) -> sha256::Hash {
+    let tag = sha256::Hash::hash(tag);
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag.as_ref());
+    engine.input(tag.as_ref());
+    for part in data {
+        engine.input(part);
+    }
+    sha256::Hash::from_engine(engine)
+}
+
+/// Tag for the hash committing a deposit key to its recipient
+pub const DEPOSIT_TWEAK_TAG: &[u8] = b"frBTC/deposit";
+
+/// Compute the tweak that commits a signer key to a deposit recipient
+///
+/// This is the BIP-340 tagged hash of the recipient's script pubkey under
+/// `DEPOSIT_TWEAK_TAG`.
+pub fn deposit_tweak(recipient: &Script) -> Scalar {
+    let hash = tagged_hash(DEPOSIT_TWEAK_TAG, &[recipient.as_bytes()]);
+    // A hash at or above the curve order occurs with negligible probability
+    Scalar::from_be_bytes(hash.to_byte_array()).expect("tweak within curve order")
+}
+
+/// Tag for the hash the signer signs to attest a deposit
+pub const DEPOSIT_ATTESTATION_TAG: &[u8] = b"frBTC/deposit-attestation";
+
+/// Compute the message the signer signs to attest a deposit to a recipient's
+/// deposit address
+///
+/// This is the BIP-340 tagged hash under `DEPOSIT_ATTESTATION_TAG` of the
+/// deposit outpoint, consensus encoded, its value in sats (u64 LE) and the
+/// recipient's script pubkey.
+pub fn deposit_attestation_message(outpoint: &OutPoint, value: u64, recipient: &Script) -> Message {
+    let hash = tagged_hash(
+        DEPOSIT_ATTESTATION_TAG,
+        &[
+            outpoint.txid.as_byte_array(),
+            &outpoint.vout.to_le_bytes(),
+            &value.to_le_bytes(),
+            recipient.as_bytes(),
+        ],
+    );
+    Message::from_digest(hash.to_byte_array())
+}
+
+/// Tag for the hash a signer heartbeat signs
+pub const HEARTBEAT_TAG: &[u8] = b"frBTC/heartbeat";
+
+/// Compute the message the signer signs to send a heartbeat
+///
+/// This is the BIP-340 tagged hash of the first input's previous output,
+/// consensus encoded, under `HEARTBEAT_TAG`. Outputs are spent once, so a
+/// signature cannot be replayed in a later transaction.
+pub fn heartbeat_message(prevout: &OutPoint) -> Message {
+    let hash = tagged_hash(
+        HEARTBEAT_TAG,
+        &[prevout.txid.as_byte_array(), &prevout.vout.to_le_bytes()],
+    );
+    Message::from_digest(hash.to_byte_array())
+}
+
+/// Derive the internal key of a recipient's deposit address from the signer key
+pub fn deposit_key(
+    signer: &XOnlyPublicKey,
+    recipient: &Script,
+) -> Result<XOnlyPublicKey, secp256k1::Error> {
+    let secp = Secp256k1::verification_only();
+    Ok(signer.add_tweak(&secp, &deposit_tweak(recipient))?.0)
+}
+
+/// Derive the P2TR script pubkey of a recipient's deposit address
+///
+/// BTC paid to this script is held by the signer, which can spend it with its
+/// own key tweaked by `deposit_tweak(recipient)`.
+pub fn deposit_script(
+    signer: &XOnlyPublicKey,
+    recipient: &Script,
+) -> Result<ScriptBuf, secp256k1::Error> {
+    let secp = Secp256k1::verification_only();
+    let (tweaked, _) = deposit_key(signer, recipient)?.tap_tweak(&secp, None);
+    Ok(ScriptBuf::new_p2tr_tweaked(tweaked))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_ids_start_at_one() {
+        assert!(Network::try_from(0).is_err());
+        for network in Network::ALL {
+            assert_eq!(Network::try_from(network as u128).unwrap(), network);
+        }
+    }
+
+    #[test]
+    fn test_only_mainnet_has_a_default_signer() {
+        for network in Network::ALL {
+            assert_eq!(
+                network.default_signer().is_some(),
+                network == Network::Mainnet
+            );
+        }
+    }
+}
@@ -0,0 +1,3571 @@
+//! Synthetic Asset Library
+//!
+//! The wrap, unwrap, signer and payment machinery shared by the SUBFROST
+//! synthetic assets. Each asset is a small alkane that implements
+//! [`SyntheticAsset`] with its name, symbol, decimals, supported networks and
+//! chain rules, then calls [`declare_synthetic_asset!`] to generate its
+//! message enum and entrypoint.
+
+use alkanes_runtime::auth::AuthenticatedResponder;
+use alkanes_runtime::{runtime::AlkaneResponder, storage::StoragePointer};
+#[allow(unused_imports)]
+use alkanes_runtime::{
+    println,
+    stdio::{stdout, Write},
+};
+use alkanes_std_factory_support::MintableToken;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::{context::Context, parcel::AlkaneTransfer, response::CallResponse};
+use anyhow::{anyhow, Result};
+use bitcoin::key::{Parity, TapTweak, TweakedPublicKey};
+use bitcoin::hashes::Hash;
+use bitcoin::secp256k1::{self, schnorr, Message, XOnlyPublicKey};
+use bitcoin::opcodes::all::OP_RETURN;
+use bitcoin::script::Instruction;
+use bitcoin::{
+    Amount, Block, CompressedPublicKey, OutPoint, PubkeyHash, PublicKey, Script, ScriptBuf,
+    ScriptHash, Transaction, TxIn, TxOut, Txid, WPubkeyHash, WScriptHash,
+};
+use fr_btc_support::{
+    deposit_attestation_message, deposit_key, heartbeat_message, CONFIGURED_NETWORK,
+};
+use metashrew_support::index_pointer::KeyValuePointer;
+use metashrew_support::utils::{consensus_decode, consensus_encode};
+use ordinals::{Artifact, Runestone};
+use protorune_support::{
+    network::{set_network, NetworkParams},
+    protostone::Protostone,
+};
+use std::sync::Arc;
+use types_support::{
+    deserialize_address_params, deserialize_payments, AddressParams, AdminAction, AuditEntry,
+    ContractInfo, Deposit, Payment, RedemptionClaim, CONTRACT_INFO_VERSION,
+};
+
+pub use fr_btc_support::Network;
+
+#[cfg(any(
+    all(
+        feature = "mainnet",
+        any(
+            feature = "testnet",
+            feature = "luckycoin",
+            feature = "dogecoin",
+            feature = "bellscoin",
+            feature = "fractal",
+            feature = "signet"
+        )
+    ),
+    all(
+        feature = "testnet",
+        any(
+            feature = "luckycoin",
+            feature = "dogecoin",
+            feature = "bellscoin",
+            feature = "fractal",
+            feature = "signet"
+        )
+    ),
+    all(
+        feature = "luckycoin",
+        any(
+            feature = "dogecoin",
+            feature = "bellscoin",
+            feature = "fractal",
+            feature = "signet"
+        )
+    ),
+    all(
+        feature = "dogecoin",
+        any(feature = "bellscoin", feature = "fractal", feature = "signet")
+    ),
+    all(feature = "bellscoin", any(feature = "fractal", feature = "signet")),
+    all(feature = "fractal", feature = "signet"),
+))]
+compile_error!("at most one network feature may be enabled");
+
+/// Get the address parameters of a network
+pub fn network_params(network: Network) -> NetworkParams {
+    let (bech32_prefix, p2pkh_prefix, p2sh_prefix) = match network {
+        Network::Regtest => ("bcrt", 0x64, 0xc4),
+        Network::Testnet | Network::Signet => ("tb", 0x6f, 0xc4),
+        // Fractal uses Bitcoin mainnet address formats
+        Network::Mainnet | Network::Fractal => ("bc", 0x00, 0x05),
+        Network::Luckycoin => ("lky", 0x2f, 0x05),
+        Network::Dogecoin => ("dc", 0x1e, 0x16),
+        Network::Bellscoin => ("bel", 0x19, 0x1e),
+    };
+    NetworkParams {
+        bech32_prefix: String::from(bech32_prefix),
+        p2pkh_prefix,
+        p2sh_prefix,
+    }
+}
+
+/// Get the storage pointer for the network chosen at initialization
+fn network_pointer() -> StoragePointer {
+    StoragePointer::from_keyword("/network")
+}
+
+/// Get the network chosen at initialization, or the build's network for
+/// instances initialized before it was stored
+pub fn network() -> Result<Network> {
+    let value = network_pointer().get();
+    if value.len() == 0 {
+        Ok(CONFIGURED_NETWORK)
+    } else {
+        Network::try_from(value[0] as u128)
+    }
+}
+
+/// Get the storage pointer for address parameters set by the owner in place
+/// of the network's own
+fn custom_params_pointer() -> StoragePointer {
+    StoragePointer::from_keyword("/network-params")
+}
+
+/// Get the address parameters in effect: the custom parameters if the owner
+/// set any, else those of the network chosen at initialization
+pub fn address_params() -> Result<AddressParams> {
+    let custom = custom_params_pointer().get();
+    if custom.len() != 0 {
+        if let Ok(params) = deserialize_address_params(&custom) {
+            return Ok(params);
+        }
+    }
+    let params = network_params(network()?);
+    Ok(AddressParams {
+        bech32_prefix: params.bech32_prefix,
+        p2pkh_prefix: params.p2pkh_prefix,
+        p2sh_prefix: params.p2sh_prefix,
+    })
+}
+
+/// Configure the network parameters for the network chosen at initialization,
+/// or the custom parameters that override them.
+pub fn configure_network() -> Result<()> {
+    let params = address_params()?;
+    set_network(NetworkParams {
+        bech32_prefix: params.bech32_prefix,
+        p2pkh_prefix: params.p2pkh_prefix,
+        p2sh_prefix: params.p2sh_prefix,
+    });
+    Ok(())
+}
+
+/// Decode a bech32 prefix packed little-endian into a u128, as names and
+/// symbols are
+pub fn unpack_bech32_prefix(packed: u128) -> Result<String> {
+    let bytes = packed
+        .to_le_bytes()
+        .into_iter()
+        .take_while(|b| *b != 0)
+        .collect::<Vec<u8>>();
+    if bytes.is_empty()
+        || !bytes
+            .iter()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+    {
+        return Err(anyhow!("bech32 prefix must be lowercase letters and digits"));
+    }
+    Ok(String::from_utf8(bytes)?)
+}
+
+/// Roles that can be bound to their own auth token
+///
+/// The owner role is always held by the contract auth token. Every other role
+/// falls back to the owner until it is granted, and the owner may always act
+/// in place of any role.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Role {
+    Owner = 0,
+    SignerAdmin = 1,
+    FeeAdmin = 2,
+    Guardian = 3,
+    Fulfiller = 4,
+    ComplianceAdmin = 5,
+}
+
+impl Role {
+    pub const ALL: [Role; 6] = [
+        Role::Owner,
+        Role::SignerAdmin,
+        Role::FeeAdmin,
+        Role::Guardian,
+        Role::Fulfiller,
+        Role::ComplianceAdmin,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Role::Owner => "owner",
+            Role::SignerAdmin => "signer-admin",
+            Role::FeeAdmin => "fee-admin",
+            Role::Guardian => "guardian",
+            Role::Fulfiller => "fulfiller",
+            Role::ComplianceAdmin => "compliance-admin",
+        }
+    }
+}
+
+impl TryFrom<u128> for Role {
+    type Error = anyhow::Error;
+
+    fn try_from(v: u128) -> Result<Self> {
+        Role::ALL
+            .into_iter()
+            .find(|role| *role as u128 == v)
+            .ok_or_else(|| anyhow!("unknown role {}", v))
+    }
+}
+
+/// Recover the script pubkey spent by an input, where the input reveals it
+///
+/// Only P2WPKH (native or nested in P2SH) and P2PKH spends carry the public
+/// key needed to rebuild the script. Other spend types, including taproot
+/// key-path spends, return `None`: the contract cannot see the prevouts, so
+/// their scripts are unknown.
+pub fn spent_script(input: &TxIn) -> Option<ScriptBuf> {
+    if input.witness.len() == 2 {
+        let pubkey = CompressedPublicKey::from_slice(input.witness.nth(1)?).ok()?;
+        let script = ScriptBuf::new_p2wpkh(&pubkey.wpubkey_hash());
+        if input.script_sig.is_empty() {
+            return Some(script);
+        }
+        // P2SH-P2WPKH pushes the witness program as its redeem script
+        let pushes = input
+            .script_sig
+            .instructions()
+            .collect::<Result<Vec<Instruction>, _>>()
+            .ok()?;
+        return match pushes.as_slice() {
+            [Instruction::PushBytes(redeem)] if redeem.as_bytes() == script.as_bytes() => {
+                Some(ScriptBuf::new_p2sh(&script.script_hash()))
+            }
+            _ => None,
+        };
+    }
+    let pushes = input
+        .script_sig
+        .instructions()
+        .collect::<Result<Vec<Instruction>, _>>()
+        .ok()?;
+    if let [Instruction::PushBytes(_), Instruction::PushBytes(key)] = pushes.as_slice() {
+        let pubkey = PublicKey::from_slice(key.as_bytes()).ok()?;
+        return Some(ScriptBuf::new_p2pkh(&pubkey.pubkey_hash()));
+    }
+    None
+}
+
+/// Operation classes that the guardian can halt independently
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PauseScope {
+    Wrap,
+    Unwrap,
+    Admin,
+}
+
+impl PauseScope {
+    fn keyword(&self) -> &'static str {
+        match self {
+            PauseScope::Wrap => "/paused/wrap",
+            PauseScope::Unwrap => "/paused/unwrap",
+            PauseScope::Admin => "/paused/admin",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            PauseScope::Wrap => "wrap",
+            PauseScope::Unwrap => "unwrap",
+            PauseScope::Admin => "admin",
+        }
+    }
+}
+
+/// Lock coins to the key path of a taproot output, as on Bitcoin
+pub fn p2tr_lock_script(key: XOnlyPublicKey) -> ScriptBuf {
+    let secp = secp256k1::Secp256k1::verification_only();
+    let (tweaked_pubkey, _) = key.tap_tweak(&secp, None);
+    ScriptBuf::new_p2tr_tweaked(tweaked_pubkey)
+}
+
+/// Dust limit for Dogecoin and the chains that share its relay policy, 0.01 coin
+///
+/// This is Dogecoin Core's `DEFAULT_DUST_LIMIT` of 1,000,000 koinu
+/// (`src/policy/policy.h`, since 1.14), below which outputs are nonstandard.
+/// Luckycoin and Bellscoin count 8-decimal units from the same codebase, and
+/// paying at least this soft limit also clears any lower hard limit a node
+/// enforces, so unwraps on all three chains use it.
+pub const DOGECOIN_DUST_LIMIT: u64 = 1_000_000;
+
+/// Lock coins to the hash of a key, for chains without segwit or taproot
+///
+/// The x-only key is lifted to its even-parity full key, so the signer must
+/// spend with that key. A signer whose secret key has an odd-parity public key
+/// negates it first, as BIP-340 signing already does for the same x-only key.
+pub fn p2pkh_lock_script(key: XOnlyPublicKey) -> ScriptBuf {
+    let pubkey = PublicKey::new(key.public_key(Parity::Even));
+    ScriptBuf::new_p2pkh(&pubkey.pubkey_hash())
+}
+
+/// A synthetic asset backed by coins held by the signer
+///
+/// Implementors only supply the asset's metadata and, where the chain differs
+/// from Bitcoin, its dust and lock rules. Everything else is provided.
+pub trait SyntheticAsset: AlkaneResponder + MintableToken + AuthenticatedResponder {
+    /// Token name set at initialization
+    const NAME: &'static str;
+    /// Token symbol set at initialization
+    const SYMBOL: &'static str;
+    /// Decimals reported by `GetDecimals` and `GetInfo`
+    const DECIMALS: u8;
+    /// Networks the asset may be initialized for
+    const NETWORKS: &'static [Network];
+
+    /// Smallest value an unwrap may pay to `script`
+    fn dust_limit(&self, script: &Script) -> u64 {
+        script.minimal_non_dust().to_sat()
+    }
+
+    /// Script pubkey locking coins to `key`, used for the signer and deposit addresses
+    fn lock_script(&self, key: XOnlyPublicKey) -> ScriptBuf {
+        p2tr_lock_script(key)
+    }
+
+    /// Get the storage pointer for the premium value
+    fn premium_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/premium")
+    }
+
+    /// Get the current premium value (0-100000000)
+    fn premium(&self) -> u128 {
+        let value = self.premium_pointer().get();
+        if value.len() == 0 {
+            500_000 // Default to 0.5% (1e8 / 200)
+        } else {
+            let mut bytes = [0u8; 16];
+            let len = std::cmp::min(value.len(), 16);
+            bytes[0..len].copy_from_slice(&value.as_ref()[0..len]);
+            u128::from_le_bytes(bytes)
+        }
+    }
+
+    /// Set the premium value internally (0-100000000)
+    fn set_premium_internal(&self, premium: u128) -> Result<()> {
+        // Ensure premium is within valid range (0 to 1 BTC in satoshis)
+        if premium > 100_000_000 {
+            return Err(anyhow!("Premium must be between 0 and 100,000,000"));
+        }
+
+        self.premium_pointer()
+            .set(Arc::new(premium.to_le_bytes().to_vec()));
+        self.checkpoint(self.premium_checkpoints_pointer(), premium);
+        Ok(())
+    }
+
+    /// Get the storage pointer for total supply checkpoints
+    fn supply_checkpoints_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/checkpoints/supply")
+    }
+
+    /// Get the storage pointer for premium checkpoints
+    fn premium_checkpoints_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/checkpoints/premium")
+    }
+
+    /// Record a value at the current height in a checkpoint list
+    ///
+    /// Each entry is the height (u64 LE) followed by the value (u128 LE); a
+    /// value changed several times in one block keeps a single entry.
+    fn checkpoint(&self, mut list: StoragePointer, value: u128) {
+        let height = self.height();
+        let mut entry = height.to_le_bytes().to_vec();
+        entry.extend(&value.to_le_bytes());
+        let length = list.length();
+        if length != 0 && self.checkpoint_height(&list, length - 1) == height {
+            list.select_index(length - 1).set(Arc::new(entry));
+        } else {
+            list.append(Arc::new(entry));
+        }
+    }
+
+    fn checkpoint_height(&self, list: &StoragePointer, index: u32) -> u64 {
+        let entry = list.select_index(index).get();
+        u64::from_le_bytes(entry.as_ref()[0..8].try_into().unwrap())
+    }
+
+    fn checkpoint_value(&self, list: &StoragePointer, index: u32) -> u128 {
+        let entry = list.select_index(index).get();
+        u128::from_le_bytes(entry.as_ref()[8..24].try_into().unwrap())
+    }
+
+    /// Get the value of a checkpoint list as of the end of a block
+    ///
+    /// Deployments that predate checkpoints have no history before their
+    /// first write, so heights before the first checkpoint are an error
+    /// rather than a misleading zero.
+    fn checkpoint_at(&self, list: &StoragePointer, height: u64) -> Result<u128> {
+        // Find the number of checkpoints at or before the height
+        let (mut low, mut high) = (0, list.length());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.checkpoint_height(list, mid) <= height {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        if low == 0 {
+            return Err(anyhow!("no checkpoint at or before height {}", height));
+        }
+        Ok(self.checkpoint_value(list, low - 1))
+    }
+
+    /// Get the storage pointer for the supply cap
+    fn supply_cap_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/supply-cap")
+    }
+
+    /// Get the maximum total supply, 0 meaning uncapped
+    fn supply_cap(&self) -> u128 {
+        self.supply_cap_pointer().get_value::<u128>()
+    }
+
+    /// Get the rate limit window length in blocks, 0 meaning no limits
+    fn rate_window(&self) -> u64 {
+        StoragePointer::from_keyword("/rate/window").get_value::<u64>()
+    }
+
+    /// Get the maximum sats wrapped per window, 0 meaning unlimited
+    fn wrap_limit(&self) -> u128 {
+        StoragePointer::from_keyword("/rate/wrap-limit").get_value::<u128>()
+    }
+
+    /// Get the maximum sats unwrapped per window, 0 meaning unlimited
+    fn unwrap_limit(&self) -> u128 {
+        StoragePointer::from_keyword("/rate/unwrap-limit").get_value::<u128>()
+    }
+
+    /// Get the storage pointer for the sats wrapped in each block, kept as a
+    /// checkpoint list
+    fn wrap_usage_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/rate/wrap-usage")
+    }
+
+    /// Get the storage pointer for the sats unwrapped in each block, kept as a
+    /// checkpoint list
+    fn unwrap_usage_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/rate/unwrap-usage")
+    }
+
+    /// Sum the usage recorded in the last `rate_window` blocks, the current
+    /// block included
+    ///
+    /// The window slides with every block rather than resetting at fixed
+    /// boundaries, and is computed from per-block entries so that changing its
+    /// length takes effect immediately.
+    fn usage_in_window(&self, list: &StoragePointer) -> u128 {
+        let window = self.rate_window();
+        if window == 0 {
+            return 0;
+        }
+        let height = self.height();
+        let mut used: u128 = 0;
+        let mut index = list.length();
+        while index > 0 {
+            index -= 1;
+            if self.checkpoint_height(list, index).saturating_add(window) <= height {
+                break;
+            }
+            used = used.saturating_add(self.checkpoint_value(list, index));
+        }
+        used
+    }
+
+    /// Add to the usage recorded for the current block
+    fn record_usage(&self, list: StoragePointer, value: u128) {
+        let length = list.length();
+        let mut used = value;
+        if length != 0 && self.checkpoint_height(&list, length - 1) == self.height() {
+            used = used.saturating_add(self.checkpoint_value(&list, length - 1));
+        }
+        self.checkpoint(list, used);
+    }
+
+    /// Get the sats that can still be wrapped in the current window
+    fn wrap_room(&self) -> u128 {
+        match (self.rate_window(), self.wrap_limit()) {
+            (0, _) | (_, 0) => u128::MAX,
+            (_, limit) => limit.saturating_sub(self.usage_in_window(&self.wrap_usage_pointer())),
+        }
+    }
+
+    /// Check whether an unwrap of `value` sats fits in the current window
+    ///
+    /// An unwrap larger than the limit is let through on its own in an
+    /// otherwise unused window so that it cannot block the queue forever.
+    fn unwrap_fits(&self, value: u128) -> bool {
+        match (self.rate_window(), self.unwrap_limit()) {
+            (0, _) | (_, 0) => true,
+            (_, limit) => {
+                let used = self.usage_in_window(&self.unwrap_usage_pointer());
+                used == 0 || used.saturating_add(value) <= limit
+            }
+        }
+    }
+
+    /// Add to the sats wrapped in the current block
+    fn record_wrap_usage(&self, value: u128) {
+        self.record_usage(self.wrap_usage_pointer(), value);
+    }
+
+    /// Add to the sats unwrapped in the current block
+    fn record_unwrap_usage(&self, value: u128) {
+        self.record_usage(self.unwrap_usage_pointer(), value);
+    }
+
+    /// Get the storage pointer for unwraps waiting on the unwrap limit
+    fn queued_unwraps_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/payments/queued")
+    }
+
+    /// Get the storage pointer for the index of the oldest queued unwrap
+    fn queued_unwraps_head_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/payments/queued-head")
+    }
+
+    /// Get the number of unwraps waiting on the unwrap limit
+    fn queued_unwrap_count(&self) -> u32 {
+        self.queued_unwraps_pointer().length() - self.queued_unwraps_head_pointer().get_value::<u32>()
+    }
+
+    /// Move queued unwraps into the payment queue, oldest first, until the
+    /// unwrap limit for the current window is reached
+    ///
+    /// # Returns
+    /// The number of unwraps released
+    fn release_queued_unwraps_internal(&self) -> Result<u32> {
+        let queue = self.queued_unwraps_pointer();
+        let mut head_ptr = self.queued_unwraps_head_pointer();
+        let mut head = head_ptr.get_value::<u32>();
+        let length = queue.length();
+        let mut released = 0;
+        while head < length {
+            let payment = deserialize_payments(&queue.select_index(head).get().as_ref().clone())?
+                .pop()
+                .ok_or("")
+                .map_err(|_| anyhow!("queued unwrap is empty"))?;
+            let value: u128 = payment.output.value.to_sat().into();
+            if !self.unwrap_fits(value) {
+                break;
+            }
+            self.record_unwrap_usage(value);
+            self.record_obligation(&payment)?;
+            self.enqueue_payment(&payment)?;
+            head += 1;
+            released += 1;
+        }
+        head_ptr.set_value::<u32>(head);
+        Ok(released)
+    }
+
+    /// Get the storage pointer for every payment released to the signer, in
+    /// release order
+    ///
+    /// Each entry is the release height (u64 LE) followed by the serialized
+    /// payment.
+    fn payment_ledger_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/payments/ledger")
+    }
+
+    /// Get the storage pointer for the number of ledger payments acknowledged as paid
+    fn acknowledged_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/payments/acknowledged")
+    }
+
+    /// Get the storage pointer for the index of the oldest unacknowledged ledger payment
+    fn ledger_head_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/payments/ledger-head")
+    }
+
+    /// Get the storage pointer for a ledger payment's acknowledged flag
+    fn ledger_acknowledged_pointer(&self, index: u32) -> StoragePointer {
+        StoragePointer::from_keyword("/payments/ledger-acknowledged/").select_value(index)
+    }
+
+    /// Get the storage pointer for the ledger indices of the payments
+    /// spendable from an outpoint
+    ///
+    /// Several unwraps in one transaction can share an outpoint, so each
+    /// entry is one u32 LE index.
+    fn ledger_index_pointer(&self, outpoint: &OutPoint) -> Result<StoragePointer> {
+        Ok(StoragePointer::from_keyword("/payments/ledger-index/")
+            .select(&consensus_encode::<OutPoint>(outpoint)?))
+    }
+
+    /// Take a newly owed payment out of the reserves
+    fn deduct_reserves(&self, payment: &Payment) {
+        self.set_reserves(
+            self.reserves()
+                .saturating_sub(payment.output.value.to_sat().into()),
+        );
+    }
+
+    /// Record a payment released to the signer in the ledger at the current height
+    fn record_obligation(&self, payment: &Payment) -> Result<()> {
+        let mut ledger = self.payment_ledger_pointer();
+        self.ledger_index_pointer(&payment.spendable)?
+            .append(Arc::new(ledger.length().to_le_bytes().to_vec()));
+        let mut entry = self.height().to_le_bytes().to_vec();
+        entry.extend(payment.serialize()?);
+        ledger.append(Arc::new(entry));
+        Ok(())
+    }
+
+    /// Get the payment recorded in a ledger entry
+    fn ledger_payment(&self, index: u32) -> Result<Payment> {
+        let entry = self.payment_ledger_pointer().select_index(index).get();
+        deserialize_payments(&entry.as_ref()[8..].to_vec())?
+            .pop()
+            .ok_or("")
+            .map_err(|_| anyhow!("ledger entry is empty"))
+    }
+
+    /// Get the storage pointer for the BTC backing synthetic tokens
+    fn reserves_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/reserves")
+    }
+
+    /// Get the sats received by the signer that are not owed back out as payments
+    ///
+    /// Deployments from before reserves were tracked have no stored value;
+    /// their reserves are taken to be the supply, which the signer must back
+    /// in full, and are written out on the next change.
+    fn reserves(&self) -> u128 {
+        let stored = self.reserves_pointer().get();
+        if stored.len() == 0 {
+            self.total_supply()
+        } else {
+            self.reserves_pointer().get_value::<u128>()
+        }
+    }
+
+    fn set_reserves(&self, v: u128) {
+        self.reserves_pointer().set_value::<u128>(v);
+    }
+
+    /// Get the storage pointer for wind-down state values
+    fn wind_down_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/wind-down")
+    }
+
+    /// Check whether the contract is winding down
+    fn winding_down(&self) -> bool {
+        self.wind_down_pointer().keyword("/active").get_value::<u8>() != 0
+    }
+
+    /// Fail if the contract is winding down
+    fn require_not_winding_down(&self) -> Result<()> {
+        if self.winding_down() {
+            return Err(anyhow!("contract is winding down, only redemptions are allowed"));
+        }
+        Ok(())
+    }
+
+    /// Get the storage pointer for redemption claims
+    fn redemption_claims_pointer(&self) -> StoragePointer {
+        self.wind_down_pointer().keyword("/claims")
+    }
+
+    /// Get the release height of a ledger entry
+    fn obligation_height(&self, index: u32) -> u64 {
+        let entry = self.payment_ledger_pointer().select_index(index).get();
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&entry.as_ref()[0..8]);
+        u64::from_le_bytes(bytes)
+    }
+
+    /// Get the payment SLA in blocks, 0 meaning payments are never overdue
+    fn payment_sla(&self) -> u64 {
+        StoragePointer::from_keyword("/payments/sla").get_value::<u64>()
+    }
+
+    /// Get the overdue payment count above which wraps are suspended, 0 meaning never
+    fn delinquency_pause_threshold(&self) -> u128 {
+        StoragePointer::from_keyword("/payments/sla-pause-threshold").get_value::<u128>()
+    }
+
+    /// Count the unacknowledged payments released longer ago than the SLA
+    ///
+    /// Ledger heights never decrease, so the payments past the SLA are a
+    /// prefix of the ledger found by binary search. Acknowledged payments
+    /// within it are then skipped; everything before the head is acknowledged.
+    ///
+    /// # Returns
+    /// The overdue count and the release height of the oldest overdue payment
+    fn overdue_payments(&self) -> (u32, Option<u64>) {
+        let sla = self.payment_sla();
+        if sla == 0 {
+            return (0, None);
+        }
+        let first = self.ledger_head_pointer().get_value::<u32>();
+        let (mut low, mut high) = (first, self.payment_ledger_pointer().length());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.obligation_height(mid).saturating_add(sla) < self.height() {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        let count = (first..low)
+            .filter(|index| self.ledger_acknowledged_pointer(*index).get_value::<u8>() == 0)
+            .count() as u32;
+        if count == 0 {
+            (0, None)
+        } else {
+            // The head is never acknowledged, so it is the oldest overdue payment
+            (count, Some(self.obligation_height(first)))
+        }
+    }
+
+    /// Check whether the overdue payment backlog is large enough to suspend wraps
+    fn delinquent(&self) -> bool {
+        let threshold = self.delinquency_pause_threshold();
+        threshold != 0 && self.overdue_payments().0 as u128 > threshold
+    }
+
+    /// Queue an unwrap payment, holding it back behind earlier queued unwraps
+    /// if the unwrap limit for the current window is used up
+    ///
+    /// A held back unwrap enters the payment ledger when it is released, so
+    /// it only becomes overdue once the signer has been asked to pay it.
+    fn schedule_unwrap(&self, payment: &Payment) -> Result<()> {
+        self.deduct_reserves(payment);
+        self.release_queued_unwraps_internal()?;
+        let value: u128 = payment.output.value.to_sat().into();
+        if self.queued_unwrap_count() == 0 && self.unwrap_fits(value) {
+            self.record_unwrap_usage(value);
+            self.record_obligation(payment)?;
+            self.enqueue_payment(payment)
+        } else {
+            self.queued_unwraps_pointer()
+                .append(Arc::new(payment.serialize()?));
+            Ok(())
+        }
+    }
+
+    /// Get the storage pointer for a script's blocklist flag
+    fn blocked_pointer(&self, script: &ScriptBuf) -> StoragePointer {
+        StoragePointer::from_keyword("/blocked/").select(&script.as_bytes().to_vec())
+    }
+
+    /// Get the storage pointer for every script that has ever been blocked
+    fn blocked_list_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/blocked-list")
+    }
+
+    /// Check whether a script pubkey is on the blocklist
+    fn is_blocked(&self, script: &ScriptBuf) -> bool {
+        self.blocked_pointer(script).get_value::<u8>() != 0
+    }
+
+    /// Check whether wrap funding inputs are screened against the blocklist
+    fn wrap_screening(&self) -> bool {
+        StoragePointer::from_keyword("/blocked-screen-wraps").get_value::<u8>() != 0
+    }
+
+    /// Check whether coins funded by inputs spending these scripts must be
+    /// refunded rather than minted against
+    ///
+    /// Screening fails closed: an input whose script cannot be recovered
+    /// (`None`, see [`spent_script`]) counts as blocked.
+    fn sources_blocked(&self, scripts: impl IntoIterator<Item = Option<ScriptBuf>>) -> bool {
+        self.wrap_screening()
+            && scripts
+                .into_iter()
+                .any(|script| match script {
+                    Some(script) => self.is_blocked(&script),
+                    None => true,
+                })
+    }
+
+    /// Check whether the inputs of a wrap must be refunded
+    fn wrap_source_blocked(&self, tx: &Transaction) -> bool {
+        self.sources_blocked(tx.input.iter().map(spent_script))
+    }
+
+    /// Get the storage pointer for cumulative protocol statistics
+    fn stats_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/stats")
+    }
+
+    /// Add to a cumulative statistic
+    fn add_stat(&self, name: &str, amount: u128) {
+        let mut stat = self.stats_pointer().keyword(name);
+        stat.set_value::<u128>(stat.get_value::<u128>().saturating_add(amount));
+    }
+
+    /// Count a wrap of `wrapped` sats that minted `minted` synthetic tokens, the
+    /// difference being the premium
+    fn record_wrap_stats(&self, wrapped: u128, minted: u128) {
+        self.add_stat("/wraps", 1);
+        self.add_stat("/wrapped", wrapped);
+        self.add_stat("/fees", wrapped.saturating_sub(minted));
+    }
+
+    /// Count an unwrap paying out to a destination output
+    fn record_unwrap_stats(&self, output: &TxOut) {
+        self.add_stat("/unwraps", 1);
+        self.add_stat("/unwrapped", output.value.to_sat().into());
+        let mut seen = self
+            .stats_pointer()
+            .keyword("/destination/")
+            .select(&output.script_pubkey.as_bytes().to_vec());
+        if seen.get_value::<u8>() == 0 {
+            seen.set_value::<u8>(1);
+            self.add_stat("/destinations", 1);
+        }
+    }
+
+    /// Get the storage pointer for recorded deposits
+    fn deposits_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/deposits")
+    }
+
+    /// Record every unprocessed output of the current block's transactions
+    /// that pays a registered deposit address, or pays the signer from a
+    /// transaction without a runestone
+    ///
+    /// Only the block being indexed is visible to the contract. Deposits
+    /// missed here can be recorded later with a signer attestation. Plain
+    /// signer payments commit to no recipient, so they are only recorded
+    /// when an input reveals the script that funded them.
+    ///
+    /// # Returns
+    /// The number of deposits recorded
+    fn record_deposits_internal(&self) -> Result<u32> {
+        let block = consensus_decode::<Block>(&mut std::io::Cursor::new(self.block()))?;
+        let signer_script = self.signer_script()?;
+        let mut recorded = 0;
+        for tx in block.txdata.iter().skip(1) {
+            let txid = tx.compute_txid();
+            let funding_scripts: Vec<ScriptBuf> = tx
+                .input
+                .iter()
+                .map(|input| spent_script(input).unwrap_or_default())
+                .collect();
+            // Signer outputs of protostone transactions belong to their wraps
+            // and unwraps
+            let plain = Runestone::decipher(tx).is_none()
+                && funding_scripts.iter().any(|script| !script.is_empty());
+            for (vout, output) in tx.output.iter().enumerate() {
+                // Deposit addresses commit to their recipient, which is the
+                // only one who can claim the deposit
+                let recipient = match self.deposit_recipient(&output.script_pubkey) {
+                    Some(recipient) => Some(recipient),
+                    None if plain && output.script_pubkey == signer_script => None,
+                    None => continue,
+                };
+                let outpoint = OutPoint {
+                    txid,
+                    vout: vout.try_into()?,
+                };
+                if self.outpoint_observed(&outpoint)? {
+                    continue;
+                }
+                self.observe_outpoint(&outpoint)?;
+                let deposit = Deposit {
+                    claimed: false,
+                    outpoint,
+                    value: output.value.to_sat(),
+                    height: self.height(),
+                    funding_scripts: funding_scripts.clone(),
+                    recipient,
+                };
+                self.deposits_pointer()
+                    .append(Arc::new(deposit.serialize()?));
+                recorded += 1;
+            }
+        }
+        Ok(recorded)
+    }
+
+    /// Get the storage pointer mapping deposit address scripts to their recipients
+    fn deposit_addresses_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/deposit-addresses/")
+    }
+
+    /// Derive the deposit address script committing to a recipient under the
+    /// current signer key
+    fn recipient_deposit_script(&self, recipient: &ScriptBuf) -> Result<ScriptBuf> {
+        Ok(self.lock_script(deposit_key(&self.signer()?, recipient)?))
+    }
+
+    /// Get the recipient a registered deposit address script commits to
+    ///
+    /// Addresses registered under an earlier signer key are ignored, as the
+    /// current signer does not hold them.
+    fn deposit_recipient(&self, script: &ScriptBuf) -> Option<ScriptBuf> {
+        let recipient = self
+            .deposit_addresses_pointer()
+            .select(&script.as_bytes().to_vec())
+            .get();
+        if recipient.len() == 0 {
+            return None;
+        }
+        let recipient = ScriptBuf::from_bytes(recipient.as_ref().clone());
+        match self.recipient_deposit_script(&recipient) {
+            Ok(current) if current == *script => Some(recipient),
+            _ => None,
+        }
+    }
+
+    /// Get the script pubkey of the output the current protostone points to
+    fn pointer_script(&self, tx: &Transaction, context: &Context) -> Result<ScriptBuf> {
+        let pointer = self
+            .protostone(tx, context)?
+            .pointer
+            .ok_or("")
+            .map_err(|_| anyhow!("no pointer in message"))?;
+        if pointer as usize >= tx.output.len() {
+            return Err(anyhow!("pointer cannot be a protomessage"));
+        }
+        Ok(tx.output[pointer as usize].script_pubkey.clone())
+    }
+
+    /// Check a BIP-340 signature by the signer key, packed little-endian
+    /// into four u128s
+    fn signed_by_signer(&self, signature: [u128; 4], message: &Message) -> Result<bool> {
+        let signature = schnorr::Signature::from_slice(
+            &signature
+                .iter()
+                .flat_map(|v| v.to_le_bytes())
+                .collect::<Vec<u8>>(),
+        )?;
+        Ok(secp256k1::Secp256k1::verification_only()
+            .verify_schnorr(&signature, message, &self.signer()?)
+            .is_ok())
+    }
+
+    /// Get the storage pointer for the append-only admin audit log
+    fn audit_log_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/audit")
+    }
+
+    /// Append an entry to the admin audit log for the current transaction
+    fn audit(&self, action: AdminAction, old_value: Vec<u8>, new_value: Vec<u8>) -> Result<()> {
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+        let entry = AuditEntry {
+            action,
+            old_value,
+            new_value,
+            height: self.height(),
+            txid: tx.compute_txid(),
+        };
+        self.audit_log_pointer()
+            .append(Arc::new(entry.serialize()?));
+        Ok(())
+    }
+
+    /// Encode a page of a storage list as its total length (u128) followed by
+    /// up to `limit` raw entries starting at `offset`
+    fn list_page(&self, list: &StoragePointer, offset: u128, limit: u128) -> Vec<u8> {
+        let length = list.length() as u128;
+        let mut result = length.to_le_bytes().to_vec();
+        let end = offset.saturating_add(limit).min(length);
+        for i in offset.min(end)..end {
+            result.extend(list.select_index(i as u32).get().as_ref());
+        }
+        result
+    }
+
+    /// Get the pause flags as [wrap, unwrap, admin]
+    fn pause_state(&self) -> Vec<u8> {
+        vec![
+            self.is_paused(PauseScope::Wrap) as u8,
+            self.is_paused(PauseScope::Unwrap) as u8,
+            self.is_paused(PauseScope::Admin) as u8,
+        ]
+    }
+
+    /// Get the rate limit settings as [window, wrap_limit, unwrap_limit] u128s
+    fn rate_limit_settings(&self) -> Vec<u8> {
+        [
+            self.rate_window() as u128,
+            self.wrap_limit(),
+            self.unwrap_limit(),
+        ]
+        .iter()
+        .flat_map(|v| v.to_le_bytes())
+        .collect()
+    }
+
+    /// Get the storage pointer for the height of the last signer heartbeat
+    fn heartbeat_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/heartbeat/last")
+    }
+
+    /// Get the storage pointer for the heartbeat staleness threshold
+    fn heartbeat_threshold_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/heartbeat/threshold")
+    }
+
+    /// Get the storage pointer for the height the threshold was last set at
+    fn heartbeat_threshold_height_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/heartbeat/threshold-height")
+    }
+
+    /// Check whether the signer has sent a heartbeat within the threshold
+    ///
+    /// Always true while no threshold is configured. Staleness is measured
+    /// from the later of the last heartbeat and the height the threshold was
+    /// set, so setting a threshold gives the signer a full period to respond.
+    fn signer_live(&self) -> bool {
+        let threshold = self.heartbeat_threshold_pointer().get_value::<u64>();
+        let since = self
+            .heartbeat_pointer()
+            .get_value::<u64>()
+            .max(self.heartbeat_threshold_height_pointer().get_value::<u64>());
+        threshold == 0 || self.height().saturating_sub(since) <= threshold
+    }
+
+    /// Get the storage pointer for delayed mint state
+    fn delayed_mint_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/delayed-mint")
+    }
+
+    /// Get the smallest mint that is delayed, 0 meaning mints are never delayed
+    fn delayed_mint_threshold(&self) -> u128 {
+        self.delayed_mint_pointer().keyword("/threshold").get_value::<u128>()
+    }
+
+    /// Get the number of blocks a delayed mint waits before it can be claimed
+    fn delayed_mint_delay(&self) -> u64 {
+        self.delayed_mint_pointer().keyword("/delay").get_value::<u64>()
+    }
+
+    /// Get the synthetic tokens recorded for delayed mints that have not been claimed yet
+    fn pending_mint_total(&self) -> u128 {
+        self.delayed_mint_pointer().keyword("/total").get_value::<u128>()
+    }
+
+    fn set_pending_mint_total(&self, v: u128) {
+        self.delayed_mint_pointer().keyword("/total").set_value::<u128>(v);
+    }
+
+    /// Get the storage pointer for the delayed mint owed to an outpoint
+    ///
+    /// The value is the amount (u128 LE) followed by the claimable height (u64 LE).
+    fn pending_mint_pointer(&self, outpoint: &OutPoint) -> Result<StoragePointer> {
+        Ok(self
+            .delayed_mint_pointer()
+            .keyword("/by-outpoint/")
+            .select(&consensus_encode::<OutPoint>(outpoint)?))
+    }
+
+    /// Record a mint for the wrap's recipient output that can be claimed
+    /// once the delay has passed
+    fn defer_mint(&self, context: &Context, tx: &Transaction, amount: u128) -> Result<()> {
+        let message = self.protostone(tx, context)?;
+        let pointer = message
+            .pointer
+            .ok_or("")
+            .map_err(|_| anyhow!("no pointer in message"))?;
+        if pointer as usize >= tx.output.len() {
+            return Err(anyhow!("pointer cannot be a protomessage"));
+        }
+        let outpoint = OutPoint {
+            txid: tx.compute_txid(),
+            vout: pointer,
+        };
+        // Several wraps in one transaction may share a recipient output
+        let mut pending = self.pending_mint_pointer(&outpoint)?;
+        let existing = pending.get();
+        let mut owed = amount;
+        if existing.len() != 0 {
+            let mut bytes = [0u8; 16];
+            bytes.copy_from_slice(&existing.as_ref()[0..16]);
+            owed += u128::from_le_bytes(bytes);
+        }
+        let mut value = owed.to_le_bytes().to_vec();
+        value.extend(&(self.height() + self.delayed_mint_delay()).to_le_bytes());
+        pending.set(Arc::new(value));
+        self.set_pending_mint_total(self.pending_mint_total().saturating_add(amount));
+        Ok(())
+    }
+
+    /// Get the storage pointer for a pause flag
+    fn pause_pointer(&self, scope: PauseScope) -> StoragePointer {
+        StoragePointer::from_keyword(scope.keyword())
+    }
+
+    /// Check whether the given class of operations is paused
+    fn is_paused(&self, scope: PauseScope) -> bool {
+        self.pause_pointer(scope).get_value::<u8>() != 0
+    }
+
+    /// Set or clear a pause flag
+    fn set_paused(&self, scope: PauseScope, paused: bool) {
+        self.pause_pointer(scope).set_value::<u8>(paused as u8);
+    }
+
+    /// Fail if the given class of operations is paused
+    fn require_not_paused(&self, scope: PauseScope) -> Result<()> {
+        if self.is_paused(scope) {
+            return Err(anyhow!("{} operations are paused", scope.name()));
+        }
+        Ok(())
+    }
+
+    /// Get the storage pointer for a role binding
+    fn role_pointer(&self, role: Role) -> StoragePointer {
+        StoragePointer::from_keyword("/roles/").select_value::<u8>(role as u8)
+    }
+
+    /// Get the auth token bound to a role, if one has been granted
+    fn role_holder(&self, role: Role) -> Result<Option<AlkaneId>> {
+        if role == Role::Owner {
+            return Ok(Some(self.auth_token()?));
+        }
+        let value = self.role_pointer(role).get();
+        if value.len() == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(value.as_ref().clone().try_into()?))
+        }
+    }
+
+    /// Require the role's auth token (or the owner's) in the incoming alkanes
+    fn only_role(&self, role: Role) -> Result<()> {
+        let holder = match self.role_holder(role)? {
+            Some(holder) if role != Role::Owner => holder,
+            _ => return self.only_owner(),
+        };
+        let context = self.context()?;
+        let owner = self.auth_token()?;
+        if context
+            .incoming_alkanes
+            .0
+            .iter()
+            .any(|v| v.id == holder || v.id == owner)
+        {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "{} role token is not in incoming alkanes",
+                role.name()
+            ))
+        }
+    }
+
+    /// Get the storage pointer for the signer's x-only pubkey
+    fn signer_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/signer")
+    }
+
+    /// Get the signer's x-only pubkey
+    /// Returns the stored signer if set, otherwise the network's default signer,
+    /// and fails on networks without one until a signer is set
+    fn signer(&self) -> Result<XOnlyPublicKey> {
+        let stored_signer = self.signer_pointer().get();
+        if stored_signer.len() > 0 {
+            Ok(XOnlyPublicKey::from_slice(&stored_signer)?)
+        } else {
+            network()?
+                .default_signer()
+                .ok_or_else(|| anyhow!("signer not set"))
+        }
+    }
+
+    /// Set the signer's x-only pubkey from a transaction output (internal implementation)
+    ///
+    /// The output at `vout` must be an `OP_RETURN` carrying only the 32-byte key.
+    /// Earlier versions stored the output's whole script pubkey, which only
+    /// parsed as a key when the script was the 32 raw key bytes, a
+    /// nonstandard output that nodes do not relay.
+    /// # Arguments
+    /// * `context` - The context of the call
+    /// * `_vout` - The output index in the transaction
+    ///
+    /// # Returns
+    /// Result indicating success or failure
+    fn set_signer_internal(&self, context: &Context, _vout: u128) -> Result<()> {
+        let vout = _vout as usize;
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+
+        if let Some(Artifact::Runestone(ref runestone)) = Runestone::decipher(&tx) {
+            let protostones = Protostone::from_runestone(runestone)?;
+            let message = &protostones[(context.vout as usize) - tx.output.len() - 1];
+
+            if message.edicts.len() != 0 {
+                return Err(anyhow!("message cannot contain edicts, only a pointer"));
+            }
+
+            let pointer = message
+                .pointer
+                .ok_or("")
+                .map_err(|_| anyhow!("no pointer in message"))?;
+
+            if pointer as usize >= tx.output.len() {
+                return Err(anyhow!("pointer cannot be a protomessage"));
+            }
+
+            if pointer as usize == vout {
+                return Err(anyhow!(
+                    "pointer cannot be equal to output spendable by synthetic"
+                ));
+            }
+
+            let script = &tx
+                .output
+                .get(vout)
+                .ok_or_else(|| anyhow!("signer output does not exist"))?
+                .script_pubkey;
+            let mut instructions = script.instructions();
+            let key = match (
+                instructions.next(),
+                instructions.next(),
+                instructions.next(),
+            ) {
+                (
+                    Some(Ok(Instruction::Op(OP_RETURN))),
+                    Some(Ok(Instruction::PushBytes(key))),
+                    None,
+                ) => XOnlyPublicKey::from_slice(key.as_bytes())
+                    .map_err(|_| anyhow!("signer output does not carry an x-only key"))?,
+                _ => return Err(anyhow!("signer output must be an OP_RETURN carrying the key")),
+            };
+            self.signer_pointer().set(Arc::new(key.serialize().to_vec()));
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "unexpected condition: execution occurred with no Protostone present"
+            ))
+        }
+    }
+
+    /// Get the storage pointer marking a signer output as already wrapped
+    fn seen_outpoint_pointer(&self, outpoint: &OutPoint) -> Result<StoragePointer> {
+        Ok(StoragePointer::from_keyword("/seen/outpoint/")
+            .select(&consensus_encode::<OutPoint>(outpoint)?))
+    }
+
+    /// Check whether a signer output has been wrapped, without marking it
+    fn outpoint_observed(&self, outpoint: &OutPoint) -> Result<bool> {
+        Ok(self.seen_outpoint_pointer(outpoint)?.get().len() != 0)
+    }
+
+    /// Check if a signer output has already been processed
+    ///
+    /// # Arguments
+    /// * `outpoint` - The output to check
+    ///
+    /// # Returns
+    /// Result indicating if the output is new (Ok) or already processed (Err)
+    fn observe_outpoint(&self, outpoint: &OutPoint) -> Result<()> {
+        let mut ptr = self.seen_outpoint_pointer(outpoint)?;
+        if ptr.get().len() != 0 {
+            Err(anyhow!("output {} already processed", outpoint))
+        } else {
+            ptr.set_value::<u8>(0x01);
+            Ok(())
+        }
+    }
+
+    /// Get the script pubkey that coins must be sent to when wrapping
+    fn signer_script(&self) -> Result<ScriptBuf> {
+        Ok(self.lock_script(self.signer()?))
+    }
+
+    /// Check whether a script is held by the signer, directly or as a
+    /// registered deposit address
+    fn is_signer_script(&self, script: &ScriptBuf) -> bool {
+        self.signer_script().is_ok_and(|signer| signer == *script)
+            || self.deposit_recipient(script).is_some()
+    }
+
+    /// Fail if any rune or protostone transfer in the transaction can land on
+    /// an output held by the signer, which cannot safely handle runes or alkanes
+    fn require_no_signer_transfers(&self, tx: &Transaction) -> Result<()> {
+        let Some(Artifact::Runestone(ref runestone)) = Runestone::decipher(tx) else {
+            return Ok(());
+        };
+        // Unallocated runes and alkanes default to the first non-OP_RETURN output
+        let default_output = tx
+            .output
+            .iter()
+            .position(|output| !output.script_pubkey.is_op_return());
+        let mut targets: Vec<u128> = runestone
+            .pointer
+            .map(u128::from)
+            .or(default_output.map(|vout| vout as u128))
+            .into_iter()
+            .collect();
+        targets.extend(runestone.edicts.iter().map(|edict| u128::from(edict.output)));
+        for protostone in Protostone::from_runestone(runestone)?.iter() {
+            targets.extend(protostone.pointer.map(u128::from));
+            targets.extend(protostone.refund.map(u128::from));
+            targets.extend(protostone.edicts.iter().map(|edict| u128::from(edict.output)));
+        }
+
+        let len = tx.output.len() as u128;
+        for target in targets {
+            // An edict to the output count splits across every non-OP_RETURN output
+            let hits_signer = if target == len {
+                tx.output.iter().any(|output| {
+                    !output.script_pubkey.is_op_return()
+                        && self.is_signer_script(&output.script_pubkey)
+                })
+            } else {
+                target < len && self.is_signer_script(&tx.output[target as usize].script_pubkey)
+            };
+            if hits_signer {
+                return Err(anyhow!("transfers cannot target a signer output"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the protostone that triggered the current execution
+    fn protostone(&self, tx: &Transaction, context: &Context) -> Result<Protostone> {
+        if let Some(Artifact::Runestone(ref runestone)) = Runestone::decipher(tx) {
+            let protostones = Protostone::from_runestone(runestone)?;
+            Ok(protostones[(context.vout as usize) - tx.output.len() - 1].clone())
+        } else {
+            Err(anyhow!("execution triggered unexpectedly -- no protostone"))
+        }
+    }
+
+    /// Append a payment to the queue at the current height
+    fn enqueue_payment(&self, payment: &Payment) -> Result<()> {
+        StoragePointer::from_keyword("/payments/byheight/")
+            .select_value(self.height())
+            .append(Arc::<Vec<u8>>::new(payment.serialize()?));
+        Ok(())
+    }
+
+    /// Claim the signer outputs of a transaction for the current wrap
+    ///
+    /// Each output can only be claimed once, so several wrap protostones in
+    /// one transaction must select disjoint outputs.
+    ///
+    /// # Arguments
+    /// * `tx` - The transaction to compute outputs for
+    /// * `selection` - Bitmask of outputs to claim, each of which must pay the
+    ///   signer; `None` claims every signer output not claimed yet
+    /// * `recipient` - Script receiving the mint, whose deposit address
+    ///   outputs are claimed alongside the signer's
+    ///
+    /// # Returns
+    /// The total value sent to the signer and the claimed output indices
+    fn compute_output(
+        &self,
+        tx: &Transaction,
+        selection: Option<u128>,
+        recipient: Option<&ScriptBuf>,
+    ) -> Result<(u128, Vec<u32>)> {
+        if let Some(mask) = selection {
+            if tx.output.len() < 128 && mask >> tx.output.len() != 0 {
+                return Err(anyhow!("output selection exceeds transaction outputs"));
+            }
+        }
+        let signer_script = self.signer_script()?;
+        let recipient_script = match recipient {
+            Some(recipient) => Some(self.recipient_deposit_script(recipient)?),
+            None => None,
+        };
+        let pays_signer = |script: &ScriptBuf| {
+            *script == signer_script || recipient_script.as_ref() == Some(script)
+        };
+        let txid = tx.compute_txid();
+        let mut total: u128 = 0;
+        let mut claimed: Vec<u32> = vec![];
+        for (vout, output) in tx.output.iter().enumerate() {
+            let selected = match selection {
+                Some(mask) => vout < 128 && mask & (1u128 << vout) != 0,
+                None => {
+                    pays_signer(&output.script_pubkey)
+                        && !self.outpoint_observed(&OutPoint {
+                            txid,
+                            vout: vout.try_into()?,
+                        })?
+                }
+            };
+            if !selected {
+                continue;
+            }
+            if !pays_signer(&output.script_pubkey) {
+                return Err(anyhow!("output {} does not pay the signer", vout));
+            }
+            let vout: u32 = vout.try_into()?;
+            self.observe_outpoint(&OutPoint { txid, vout })?;
+            total += <u64 as Into<u128>>::into(output.value.to_sat());
+            claimed.push(vout);
+        }
+
+        Ok((total, claimed))
+    }
+
+    /// Get the amount of synthetic tokens to burn from the incoming alkanes
+    ///
+    /// # Arguments
+    /// * `context` - The context of the call
+    ///
+    /// # Returns
+    /// The amount of synthetic tokens to burn
+    fn burn_input(&self, context: &Context) -> Result<u64> {
+        let value = context
+            .incoming_alkanes
+            .0
+            .iter()
+            .find(|v| context.myself == v.id)
+            .ok_or("")
+            .map_err(|_| anyhow!("must spend synthetics into message"))?
+            .value
+            .try_into()?;
+
+        Ok(value)
+    }
+
+    /// Burn synthetic tokens and create a payment for unwrapping to BTC
+    ///
+    /// # Arguments
+    /// * `context` - The context of the call
+    /// * `vout` - The signer output the payment is spendable from
+    ///
+    /// # Returns
+    /// The amount of synthetic tokens burned
+    fn burn(&self, context: &Context, vout: usize) -> Result<u64> {
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+        let txid = tx.compute_txid();
+
+        if let Some(Artifact::Runestone(ref runestone)) = Runestone::decipher(&tx) {
+            let protostones = Protostone::from_runestone(runestone)?;
+            let message = &protostones[(context.vout as usize) - tx.output.len() - 1];
+
+            if message.edicts.len() != 0 {
+                return Err(anyhow!("message cannot contain edicts, only a pointer"));
+            }
+
+            let pointer = message
+                .pointer
+                .ok_or("")
+                .map_err(|_| anyhow!("no pointer in message"))?;
+
+            if pointer as usize >= tx.output.len() {
+                return Err(anyhow!("pointer cannot be a protomessage"));
+            }
+
+            // The signer fulfills the payment by spending this output, so it
+            // must hold it; otherwise the payment could never be acknowledged
+            // and would count against the signer as overdue. The pointer
+            // cannot be this output, as the destination cannot be the signer.
+            let spendable = tx
+                .output
+                .get(vout)
+                .ok_or_else(|| anyhow!("unwrap output {} does not exist", vout))?;
+            if !self.is_signer_script(&spendable.script_pubkey) {
+                return Err(anyhow!("unwrap output {} must pay the signer", vout));
+            }
+
+            self.require_no_signer_transfers(&tx)?;
+
+            let destination = &tx.output[pointer as usize].script_pubkey;
+            let value = self.burn_input(context)?;
+            self.check_unwrap_destination(destination, value)?;
+
+            // Create a payment record for the unwrap
+            let payment = Payment {
+                output: TxOut {
+                    script_pubkey: destination.clone(),
+                    value: Amount::from_sat(value),
+                },
+                spendable: OutPoint {
+                    txid,
+                    vout: vout.try_into()?,
+                },
+            };
+            // Store the payment record, or queue it behind the unwrap limit
+            self.schedule_unwrap(&payment)?;
+            self.record_unwrap_stats(&payment.output);
+
+            // Burned synthetic tokens stay with the contract and are never
+            // spendable again, so they leave the supply; otherwise every unwrap
+            // would permanently use up room under the supply cap
+            self.set_total_supply(self.total_supply().saturating_sub(value.into()));
+            Ok(value)
+        } else {
+            Err(anyhow!("execution triggered unexpectedly -- no protostone"))
+        }
+    }
+
+    /// Wrap BTC to synthetic tokens by verifying a Bitcoin transaction
+    ///
+    /// # Arguments
+    /// * `context` - The context of the call
+    ///
+    /// # Returns
+    /// An AlkaneTransfer representing the minted synthetic tokens
+    fn exchange(&self, context: &Context, selection: Option<u128>) -> Result<AlkaneTransfer> {
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+
+        // Claim the signer outputs, rejecting any that were already processed
+        let recipient = self.pointer_script(&tx, context).ok();
+        let (payout, claimed) = self.compute_output(&tx, selection, recipient.as_ref())?;
+        let txid = tx.compute_txid();
+        let spendable = claimed.first().map(|vout| OutPoint { txid, vout: *vout });
+
+        self.mint_wrapped(context, &tx, spendable, payout, self.wrap_source_blocked(&tx))
+    }
+
+    /// Mint synthetic tokens against BTC that has reached the signer
+    ///
+    /// # Arguments
+    /// * `context` - The context of the call
+    /// * `tx` - The transaction being executed
+    /// * `spendable` - The first signer output backing the mint
+    /// * `payout` - The BTC received by the signer
+    /// * `source_blocked` - Whether the BTC came from a blocked script
+    ///
+    /// # Returns
+    /// An AlkaneTransfer representing the minted synthetic tokens
+    fn mint_wrapped(
+        &self,
+        context: &Context,
+        tx: &Transaction,
+        spendable: Option<OutPoint>,
+        payout: u128,
+        source_blocked: bool,
+    ) -> Result<AlkaneTransfer> {
+        // Minted synthetic tokens and any refund must not be sent to the signer
+        self.require_no_signer_transfers(tx)?;
+
+        self.set_reserves(self.reserves().saturating_add(payout));
+
+        // Return BTC from blocked sources instead of minting against it
+        if source_blocked {
+            self.queue_refund(context, tx, spendable, payout)?;
+            return self.mint(&context, 0);
+        }
+
+        // Apply premium (subtract fee)
+        let adjusted_payout = self.apply_premium(payout);
+
+        // Clamp to the supply cap and wrap limit, refunding the BTC backing any excess
+        let (mint_amount, refunded) =
+            self.clamp_mint(context, tx, spendable, payout, adjusted_payout)?;
+        self.record_wrap_stats(payout - refunded, mint_amount);
+
+        // Hold back large wraps until they are buried deep enough to survive a reorg
+        if self.mint_delayed(mint_amount) {
+            self.defer_mint(context, tx, mint_amount)?;
+            return self.mint(&context, 0);
+        }
+
+        // Mint synthetic tokens with adjusted payout
+        self.mint(&context, mint_amount)
+    }
+
+    /// Subtract the premium from a BTC amount
+    fn apply_premium(&self, payout: u128) -> u128 {
+        let premium = self.premium();
+        if premium > 0 && payout > 0 {
+            // Calculate fee based on premium
+            // For each Bitcoin (100,000,000 satoshis), subtract premium amount
+            let fee = (payout * premium) / 100_000_000;
+            payout.saturating_sub(fee)
+        } else {
+            payout
+        }
+    }
+
+    /// Fail if wraps are paused, winding down or suspended by signer health
+    fn require_wraps_open(&self) -> Result<()> {
+        self.require_not_paused(PauseScope::Wrap)?;
+        self.require_not_winding_down()?;
+        if !self.signer_live() {
+            return Err(anyhow!("signer heartbeat is stale, wraps are suspended"));
+        }
+        if self.delinquent() {
+            return Err(anyhow!("overdue payment backlog exceeds threshold, wraps are suspended"));
+        }
+        Ok(())
+    }
+
+    /// Get how much of a mint fits under the supply cap and the wrap limit
+    /// for the current window
+    fn mint_room(&self, amount: u128) -> u128 {
+        let mut room = amount;
+        let cap = self.supply_cap();
+        if cap != 0 {
+            room = room.min(
+                cap.saturating_sub(self.total_supply())
+                    .saturating_sub(self.pending_mint_total()),
+            );
+        }
+        let wrap_room = self.wrap_room();
+        if wrap_room != u128::MAX {
+            room = room.min(self.apply_premium(wrap_room));
+        }
+        room
+    }
+
+    /// Get the synthetic tokens a wrap of `payout` sats mints after the
+    /// premium and limits, nothing when its source is blocked
+    fn wrap_quote(&self, payout: u128, source_blocked: bool) -> u128 {
+        if source_blocked {
+            return 0;
+        }
+        self.mint_room(self.apply_premium(payout))
+    }
+
+    /// Check whether a mint of `amount` is held back by the delayed mint policy
+    fn mint_delayed(&self, amount: u128) -> bool {
+        let threshold = self.delayed_mint_threshold();
+        threshold != 0 && amount >= threshold
+    }
+
+    /// Fail if unwraps are paused or the contract is winding down
+    fn require_unwraps_open(&self) -> Result<()> {
+        self.require_not_paused(PauseScope::Unwrap)?;
+        self.require_not_winding_down()
+    }
+
+    /// Fail if an unwrap of `value` may not pay out to `destination`
+    fn check_unwrap_destination(&self, destination: &ScriptBuf, value: u64) -> Result<()> {
+        if self.is_blocked(destination) {
+            return Err(anyhow!("unwrap destination is blocked"));
+        }
+        if destination.is_op_return() {
+            return Err(anyhow!("unwrap destination cannot be an OP_RETURN"));
+        }
+        if self.is_signer_script(destination) {
+            return Err(anyhow!("unwrap destination cannot be the signer"));
+        }
+        if value < self.dust_limit(destination) {
+            return Err(anyhow!("unwrap amount is below the destination's dust limit"));
+        }
+        Ok(())
+    }
+
+    /// Get the highest dust limit of any standard unwrap destination
+    ///
+    /// A quote has no destination, so it only promises amounts every standard
+    /// output type can receive.
+    fn unwrap_dust_floor(&self) -> u64 {
+        [
+            ScriptBuf::new_p2pkh(&PubkeyHash::all_zeros()),
+            ScriptBuf::new_p2sh(&ScriptHash::all_zeros()),
+            ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()),
+            ScriptBuf::new_p2wsh(&WScriptHash::all_zeros()),
+            ScriptBuf::new_p2tr_tweaked(
+                XOnlyPublicKey::from_slice(&[1; 32])
+                    .map(TweakedPublicKey::dangerous_assume_tweaked)
+                    .expect("valid x-only key"),
+            ),
+        ]
+        .iter()
+        .map(|script| self.dust_limit(script))
+        .max()
+        .unwrap_or_default()
+    }
+
+    /// Limit a mint to the room left under the supply cap and the wrap limit
+    /// for the current window
+    ///
+    /// The BTC backing the excess has already been sent to the signer, so
+    /// instead of reverting, a refund payment for its share of `payout` is
+    /// queued to the protostone's refund output (or its pointer if no refund
+    /// is set).
+    ///
+    /// # Arguments
+    /// * `context` - The context of the call
+    /// * `tx` - The wrap transaction
+    /// * `spendable` - The first signer output backing the mint
+    /// * `payout` - The BTC sent to the signer
+    /// * `amount` - The synthetic tokens that would be minted without a cap
+    ///
+    /// # Returns
+    /// The synthetic tokens amount to mint and the BTC refunded
+    fn clamp_mint(
+        &self,
+        context: &Context,
+        tx: &Transaction,
+        spendable: Option<OutPoint>,
+        payout: u128,
+        amount: u128,
+    ) -> Result<(u128, u128)> {
+        let room = self.mint_room(amount);
+        if amount <= room {
+            self.record_wrap_usage(payout);
+            return Ok((amount, 0));
+        }
+
+        let excess = amount - room;
+        let refund = payout * excess / amount;
+        self.record_wrap_usage(payout - refund);
+        self.queue_refund(context, tx, spendable, refund)?;
+        Ok((room, refund))
+    }
+
+    /// Queue a refund of BTC received by the signer in a wrap transaction
+    ///
+    /// The refund goes to the protostone's refund output, or its pointer if
+    /// no refund is set, and is spendable from the given signer output.
+    fn queue_refund(
+        &self,
+        context: &Context,
+        tx: &Transaction,
+        spendable: Option<OutPoint>,
+        refund: u128,
+    ) -> Result<()> {
+        if refund == 0 {
+            return Ok(());
+        }
+        let message = self.protostone(tx, context)?;
+        let target = message
+            .refund
+            .or(message.pointer)
+            .ok_or("")
+            .map_err(|_| anyhow!("no refund pointer in message"))?;
+        if target as usize >= tx.output.len() {
+            return Err(anyhow!("refund pointer cannot be a protomessage"));
+        }
+        let script_pubkey = tx.output[target as usize].script_pubkey.clone();
+        if self.is_signer_script(&script_pubkey) {
+            return Err(anyhow!("refund cannot be paid to the signer"));
+        }
+        if self.is_blocked(&script_pubkey) {
+            return Err(anyhow!("refund destination is blocked"));
+        }
+        let spendable = spendable
+            .ok_or("")
+            .map_err(|_| anyhow!("no signer output to refund from"))?;
+        let payment = Payment {
+            output: TxOut {
+                script_pubkey,
+                value: Amount::from_sat(refund.try_into()?),
+            },
+            spendable,
+        };
+        self.deduct_reserves(&payment);
+        self.record_obligation(&payment)?;
+        self.enqueue_payment(&payment)
+    }
+
+    /// Get all pending payments at the current height (internal implementation)
+    /// # Returns
+    /// A vector of serialized Payment objects
+    fn get_pending_payments_internal(&self) -> Vec<u8> {
+        let payments = StoragePointer::from_keyword("/payments/byheight/")
+            .select_value(self.height())
+            .get_list()
+            .into_iter()
+            .fold(Vec::<u8>::new(), |r, v| {
+                let mut result = Vec::<u8>::with_capacity(r.len() + v.len());
+                result.extend(&r);
+                result.extend(v.as_ref());
+                result
+            });
+        payments
+    }
+
+    /// Initialize the contract
+    ///
+    /// A single owner auth token is created by the auth token factory and
+    /// returned to the caller, so ownership always rests on a token this
+    /// contract minted rather than one chosen by whoever initializes it.
+    fn initialize(&self, network: u128) -> Result<CallResponse> {
+        self.observe_initialization()?;
+        let network = Network::try_from(network)?;
+        if !Self::NETWORKS.contains(&network) {
+            return Err(anyhow!("{} cannot be deployed to {:?}", Self::SYMBOL, network));
+        }
+        network_pointer().set(Arc::new(vec![network as u8]));
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+        let auth_token = self.deploy_auth_token(1)?;
+        let auth_token_id = auth_token.id.clone();
+        response.alkanes.0.push(auth_token);
+        self.set_name_and_symbol_str(Self::NAME.to_string(), Self::SYMBOL.to_string());
+        self.checkpoint(self.supply_checkpoints_pointer(), self.total_supply());
+        self.checkpoint(self.premium_checkpoints_pointer(), self.premium());
+        self.set_reserves(0);
+        self.audit(AdminAction::Initialize, vec![], auth_token_id.into())?;
+        Ok(response)
+    }
+    /// Set the signer script pubkey
+    fn set_signer(&self, vout: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.only_role(Role::SignerAdmin)?;
+        self.require_not_paused(PauseScope::Admin)?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let old_signer = self
+            .signer()
+            .map(|signer| signer.serialize().to_vec())
+            .unwrap_or_default();
+        self.set_signer_internal(&context, vout)?;
+        response.data = self.signer()?.serialize().to_vec();
+        self.audit(AdminAction::SetSigner, old_signer, response.data.clone())?;
+        Ok(response)
+    }
+
+    /// Wrap BTC to synthetic tokens
+    fn wrap(&self) -> Result<CallResponse> {
+        self.wrap_selected(None)
+    }
+
+    /// Wrap BTC to synthetic tokens from the selected signer outputs only
+    fn wrap_outputs(&self, outputs: u128) -> Result<CallResponse> {
+        self.wrap_selected(Some(outputs))
+    }
+
+    /// Wrap BTC to synthetic tokens from the selected signer outputs, or all of them
+    fn wrap_selected(&self, selection: Option<u128>) -> Result<CallResponse> {
+        configure_network()?;
+        self.require_wraps_open()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        response.alkanes.0.push(self.exchange(&context, selection)?);
+        Ok(response)
+    }
+
+    /// Unwrap synthetic tokens to BTC
+    fn unwrap(&self, vout: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.require_unwraps_open()?;
+        let context = self.context()?;
+
+        if context.caller.clone() != (AlkaneId { tx: 0, block: 0 }) {
+            return Err(anyhow!("must be called by EOA"));
+        }
+
+        if context.incoming_alkanes.0.len() != 1
+            || context.incoming_alkanes.0[0].id != context.myself
+        {
+            return Err(anyhow!("must only send the synthetic token as input"));
+        }
+
+        let burn_value = self.burn(&context, vout as usize)?;
+
+        let mut burn_response = CallResponse::default();
+        burn_response.data = burn_value.to_le_bytes().to_vec();
+        Ok(burn_response)
+    }
+
+    /// Get the signer address
+    fn get_signer(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = self.signer()?.serialize().to_vec();
+        Ok(response)
+    }
+
+    /// Get pending payments
+    fn get_pending_payments(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut payments = CallResponse::forward(&context.incoming_alkanes);
+        payments.data = self.get_pending_payments_internal();
+        Ok(payments)
+    }
+
+    /// Get the aggregate contract state
+    fn get_info(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let pending_payments = self.payment_ledger_pointer().length()
+            - self.acknowledged_pointer().get_value::<u32>();
+        let info = ContractInfo {
+            version: CONTRACT_INFO_VERSION,
+            name: self.name(),
+            symbol: self.symbol(),
+            decimals: Self::DECIMALS,
+            total_supply: self.total_supply(),
+            premium: self.premium(),
+            signer: self
+                .signer()
+                .map(|signer| signer.serialize().to_vec())
+                .unwrap_or_default(),
+            paused: [
+                self.is_paused(PauseScope::Wrap),
+                self.is_paused(PauseScope::Unwrap),
+                self.is_paused(PauseScope::Admin),
+            ],
+            signer_script: self.signer_script().unwrap_or_default(),
+            auth_token: self.auth_token()?,
+            pending_payments: pending_payments as u128,
+            reserves: self.reserves(),
+        };
+        response.data = info.serialize()?;
+        Ok(response)
+    }
+
+    /// Get token name
+    fn get_name(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+        response.data = self.name().into_bytes().to_vec();
+        Ok(response)
+    }
+
+    /// Get token symbol
+    fn get_symbol(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+        response.data = self.symbol().into_bytes().to_vec();
+        Ok(response)
+    }
+
+    fn get_total_supply(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes.clone());
+
+        response.data = self.total_supply().to_le_bytes().to_vec();
+
+        Ok(response)
+    }
+
+    /// Quote the synthetic tokens minted by a wrap and the height they can
+    /// be claimed at
+    ///
+    /// Screening applies to the inputs of the quoting transaction, so a
+    /// simulation funded like the wrap sees it refunded. The height is the
+    /// current one unless the mint is delayed.
+    fn quote_wrap(&self, amount: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.require_wraps_open()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+        let minted = self.wrap_quote(amount, self.wrap_source_blocked(&tx));
+        let mut mintable_at = self.height();
+        if self.mint_delayed(minted) {
+            mintable_at += self.delayed_mint_delay();
+        }
+        for value in [minted, mintable_at as u128] {
+            response.data.extend(&value.to_le_bytes());
+        }
+        Ok(response)
+    }
+
+    /// Quote the BTC paid out by an unwrap
+    ///
+    /// Unwraps carry no fee, so the signer pays exactly the burned amount. The
+    /// quote fails where any standard destination would reject the amount.
+    fn quote_unwrap(&self, amount: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.require_unwraps_open()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let payout: u64 = amount.try_into()?;
+        if payout < self.unwrap_dust_floor() {
+            return Err(anyhow!("unwrap amount is below the destination's dust limit"));
+        }
+        response.data = (payout as u128).to_le_bytes().to_vec();
+        Ok(response)
+    }
+
+    /// Get the network chosen at initialization
+    fn get_network(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = vec![network()? as u8];
+        Ok(response)
+    }
+
+    /// Override the network's address parameters, or restore its own
+    ///
+    /// # Arguments
+    /// * `bech32_prefix` - The bech32 prefix packed little-endian, 0 to restore the defaults
+    /// * `p2pkh_prefix` - The P2PKH address version byte
+    /// * `p2sh_prefix` - The P2SH address version byte
+    fn set_network_params(
+        &self,
+        bech32_prefix: u128,
+        p2pkh_prefix: u128,
+        p2sh_prefix: u128,
+    ) -> Result<CallResponse> {
+        configure_network()?;
+        self.only_owner()?;
+        self.require_not_paused(PauseScope::Admin)?;
+        let context = self.context()?;
+        let response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let old_params = address_params()?;
+        if bech32_prefix == 0 {
+            custom_params_pointer().set(Arc::new(vec![]));
+        } else {
+            let params = AddressParams {
+                bech32_prefix: unpack_bech32_prefix(bech32_prefix)?,
+                p2pkh_prefix: u8::try_from(p2pkh_prefix)
+                    .map_err(|_| anyhow!("p2pkh prefix must fit in a byte"))?,
+                p2sh_prefix: u8::try_from(p2sh_prefix)
+                    .map_err(|_| anyhow!("p2sh prefix must fit in a byte"))?,
+            };
+            custom_params_pointer().set(Arc::new(params.serialize()));
+        }
+        configure_network()?;
+        self.audit(
+            AdminAction::SetNetworkParams,
+            old_params.serialize(),
+            address_params()?.serialize(),
+        )?;
+        Ok(response)
+    }
+
+    /// Get the address parameters in effect
+    fn get_network_params(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = address_params()?.serialize();
+        Ok(response)
+    }
+
+    /// Get cumulative protocol statistics
+    fn get_stats(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        for name in [
+            "/wraps",
+            "/unwraps",
+            "/wrapped",
+            "/unwrapped",
+            "/fees",
+            "/destinations",
+        ] {
+            let value = self.stats_pointer().keyword(name).get_value::<u128>();
+            response.data.extend(&value.to_le_bytes());
+        }
+        Ok(response)
+    }
+
+    /// Get the total supply as of the end of a block
+    fn get_total_supply_at(&self, height: u128) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let supply = self.checkpoint_at(&self.supply_checkpoints_pointer(), height.try_into()?)?;
+        response.data = supply.to_le_bytes().to_vec();
+        Ok(response)
+    }
+
+    /// Get token decimals
+    fn get_decimals(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+        response.data = vec![Self::DECIMALS];
+        Ok(response)
+    }
+
+    /// Set the premium value (owner only)
+    fn set_premium(&self, premium: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.only_role(Role::FeeAdmin)?;
+        self.require_not_paused(PauseScope::Admin)?;
+        let context = self.context()?;
+        let response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        // Validate and set premium
+        if premium > 100_000_000 {
+            return Err(anyhow!("Premium must be between 0 and 100,000,000"));
+        }
+
+        // Set the premium value
+        let old_premium = self.premium();
+        self.set_premium_internal(premium)?;
+        self.audit(
+            AdminAction::SetPremium,
+            old_premium.to_le_bytes().to_vec(),
+            premium.to_le_bytes().to_vec(),
+        )?;
+
+        Ok(response)
+    }
+
+    /// Get the current premium value
+    fn get_premium(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        // Get the premium value and return it
+        let premium = self.premium();
+        response.data = premium.to_le_bytes().to_vec();
+
+        Ok(response)
+    }
+
+    /// Get the premium as of the end of a block
+    fn get_premium_at(&self, height: u128) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let premium = self.checkpoint_at(&self.premium_checkpoints_pointer(), height.try_into()?)?;
+        response.data = premium.to_le_bytes().to_vec();
+        Ok(response)
+    }
+
+    /// Bind the guardian role to an auth token (owner only)
+    fn set_guardian(&self, block: u128, tx: u128) -> Result<CallResponse> {
+        self.grant_role(Role::Guardian as u128, block, tx)
+    }
+
+    /// Bind a role to an auth token (owner only)
+    fn grant_role(&self, role: u128, block: u128, tx: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.only_owner()?;
+        self.require_not_paused(PauseScope::Admin)?;
+        let context = self.context()?;
+        let response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let role = Role::try_from(role)?;
+        if role == Role::Owner {
+            return Err(anyhow!("owner role cannot be granted"));
+        }
+        let old_holder = self.role_pointer(role).get().as_ref().clone();
+        let new_holder: Vec<u8> = AlkaneId { block, tx }.into();
+        self.role_pointer(role).set(Arc::new(new_holder.clone()));
+        self.audit(
+            AdminAction::GrantRole,
+            [vec![role as u8], old_holder].concat(),
+            [vec![role as u8], new_holder].concat(),
+        )?;
+        Ok(response)
+    }
+
+    /// Move ownership to a new auth token (owner only)
+    ///
+    /// The new token must be present in the incoming alkanes so that ownership
+    /// cannot be handed to a token nobody controls.
+    fn migrate_auth_token(&self, block: u128, tx: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.only_owner()?;
+        self.require_not_paused(PauseScope::Admin)?;
+        let context = self.context()?;
+        let response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let new_token = AlkaneId { block, tx };
+        if !context.incoming_alkanes.0.iter().any(|v| v.id == new_token) {
+            return Err(anyhow!("new auth token is not in incoming alkanes"));
+        }
+        let old_token = self.auth_token()?;
+        self.set_auth_token(new_token.clone())?;
+        self.audit(AdminAction::MigrateAuthToken, old_token.into(), new_token.into())?;
+        Ok(response)
+    }
+
+    /// Set the maximum total supply (owner only)
+    fn set_supply_cap(&self, cap: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.only_owner()?;
+        self.require_not_paused(PauseScope::Admin)?;
+        let context = self.context()?;
+        let response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let old_cap = self.supply_cap();
+        self.supply_cap_pointer().set_value::<u128>(cap);
+        self.audit(
+            AdminAction::SetSupplyCap,
+            old_cap.to_le_bytes().to_vec(),
+            cap.to_le_bytes().to_vec(),
+        )?;
+        Ok(response)
+    }
+
+    /// Get the maximum total supply
+    fn get_supply_cap(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = self.supply_cap().to_le_bytes().to_vec();
+        Ok(response)
+    }
+
+    /// Set the rate limit window and per-window limits (owner only)
+    fn set_rate_limits(
+        &self,
+        window: u128,
+        wrap_limit: u128,
+        unwrap_limit: u128,
+    ) -> Result<CallResponse> {
+        configure_network()?;
+        self.only_owner()?;
+        self.require_not_paused(PauseScope::Admin)?;
+        let context = self.context()?;
+        let response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let old_settings = self.rate_limit_settings();
+        StoragePointer::from_keyword("/rate/window").set_value::<u64>(window.try_into()?);
+        StoragePointer::from_keyword("/rate/wrap-limit").set_value::<u128>(wrap_limit);
+        StoragePointer::from_keyword("/rate/unwrap-limit").set_value::<u128>(unwrap_limit);
+        self.audit(AdminAction::SetRateLimits, old_settings, self.rate_limit_settings())?;
+        Ok(response)
+    }
+
+    /// Release queued unwraps that now fit under the unwrap limit
+    fn release_queued_unwraps(&self) -> Result<CallResponse> {
+        configure_network()?;
+        self.require_not_paused(PauseScope::Unwrap)?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let released = self.release_queued_unwraps_internal()?;
+        response.data = (released as u128).to_le_bytes().to_vec();
+        Ok(response)
+    }
+
+    /// Get the rate limits and usage in the current window
+    fn get_rate_limits(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        for value in [
+            self.rate_window() as u128,
+            self.wrap_limit(),
+            self.unwrap_limit(),
+            self.usage_in_window(&self.wrap_usage_pointer()),
+            self.usage_in_window(&self.unwrap_usage_pointer()),
+            self.queued_unwrap_count() as u128,
+        ] {
+            response.data.extend(&value.to_le_bytes());
+        }
+        Ok(response)
+    }
+
+    /// Block or unblock the script pubkey of an output (compliance-admin)
+    fn set_script_blocked(&self, vout: u128, blocked: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.only_role(Role::ComplianceAdmin)?;
+        self.require_not_paused(PauseScope::Admin)?;
+        let context = self.context()?;
+        let response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+        let script = tx
+            .output
+            .get(vout as usize)
+            .ok_or("")
+            .map_err(|_| anyhow!("vout out of range"))?
+            .script_pubkey
+            .clone();
+        let mut flag = self.blocked_pointer(&script);
+        if flag.get().len() == 0 {
+            self.blocked_list_pointer()
+                .append(Arc::new(script.as_bytes().to_vec()));
+        }
+        let old_flag = flag.get_value::<u8>();
+        flag.set_value::<u8>((blocked != 0) as u8);
+        self.audit(
+            AdminAction::SetScriptBlocked,
+            [script.as_bytes(), &[old_flag]].concat(),
+            [script.as_bytes(), &[(blocked != 0) as u8]].concat(),
+        )?;
+        Ok(response)
+    }
+
+    /// Enable or disable wrap input screening (compliance-admin)
+    fn set_wrap_screening(&self, enabled: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.only_role(Role::ComplianceAdmin)?;
+        self.require_not_paused(PauseScope::Admin)?;
+        let context = self.context()?;
+        let response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let old_enabled = self.wrap_screening();
+        StoragePointer::from_keyword("/blocked-screen-wraps").set_value::<u8>((enabled != 0) as u8);
+        self.audit(
+            AdminAction::SetWrapScreening,
+            vec![old_enabled as u8],
+            vec![(enabled != 0) as u8],
+        )?;
+        Ok(response)
+    }
+
+    /// Get the currently blocked script pubkeys
+    fn get_blocked_scripts(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        for script in self.blocked_list_pointer().get_list() {
+            let script = ScriptBuf::from_bytes(script.as_ref().clone());
+            if self.is_blocked(&script) {
+                response.data.extend(&(script.len() as u32).to_le_bytes());
+                response.data.extend(script.as_bytes());
+            }
+        }
+        Ok(response)
+    }
+
+    /// Remove a role binding (owner only)
+    fn revoke_role(&self, role: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.only_owner()?;
+        self.require_not_paused(PauseScope::Admin)?;
+        let context = self.context()?;
+        let response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let role = Role::try_from(role)?;
+        if role == Role::Owner {
+            return Err(anyhow!("owner role cannot be revoked"));
+        }
+        let old_holder = self.role_pointer(role).get().as_ref().clone();
+        self.role_pointer(role).set(Arc::new(vec![]));
+        self.audit(
+            AdminAction::RevokeRole,
+            [vec![role as u8], old_holder].concat(),
+            vec![role as u8],
+        )?;
+        Ok(response)
+    }
+
+    /// Get the current role holders
+    ///
+    /// Roles that have not been granted report the owner's auth token.
+    fn get_roles(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let owner = self.auth_token()?;
+        for role in Role::ALL {
+            let holder = self.role_holder(role)?.unwrap_or(owner.clone());
+            response.data.extend(&(role as u128).to_le_bytes());
+            response.data.extend(&holder.block.to_le_bytes());
+            response.data.extend(&holder.tx.to_le_bytes());
+        }
+        Ok(response)
+    }
+
+    /// Pause the selected operations (guardian or owner)
+    ///
+    /// Pausing is one-directional: the guardian can only raise flags, never clear them.
+    fn pause(&self, wrap: u128, unwrap: u128, admin: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.only_role(Role::Guardian)?;
+        let context = self.context()?;
+        let response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let old_state = self.pause_state();
+        for (scope, selected) in [
+            (PauseScope::Wrap, wrap),
+            (PauseScope::Unwrap, unwrap),
+            (PauseScope::Admin, admin),
+        ] {
+            if selected != 0 {
+                self.set_paused(scope, true);
+            }
+        }
+        self.audit(AdminAction::Pause, old_state, self.pause_state())?;
+        Ok(response)
+    }
+
+    /// Unpause the selected operations (owner only)
+    fn unpause(&self, wrap: u128, unwrap: u128, admin: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.only_owner()?;
+        let context = self.context()?;
+        let response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let old_state = self.pause_state();
+        for (scope, selected) in [
+            (PauseScope::Wrap, wrap),
+            (PauseScope::Unwrap, unwrap),
+            (PauseScope::Admin, admin),
+        ] {
+            if selected != 0 {
+                self.set_paused(scope, false);
+            }
+        }
+        self.audit(AdminAction::Unpause, old_state, self.pause_state())?;
+        Ok(response)
+    }
+
+    /// Get the pause flags
+    fn get_pause_state(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = self.pause_state();
+        Ok(response)
+    }
+
+    /// Get a page of the admin audit log
+    fn get_audit_log(&self, offset: u128, limit: u128) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = self.list_page(&self.audit_log_pointer(), offset, limit);
+        Ok(response)
+    }
+
+    /// Record a signer heartbeat
+    ///
+    /// The four parameters are a BIP-340 signature by the signer key, packed
+    /// little-endian, over `heartbeat_message` of the transaction's first input.
+    fn heartbeat(
+        &self,
+        signature_0: u128,
+        signature_1: u128,
+        signature_2: u128,
+        signature_3: u128,
+    ) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+        let prevout = tx
+            .input
+            .first()
+            .ok_or("")
+            .map_err(|_| anyhow!("heartbeat transaction has no inputs"))?
+            .previous_output;
+        if !self.signed_by_signer(
+            [signature_0, signature_1, signature_2, signature_3],
+            &heartbeat_message(&prevout),
+        )? {
+            return Err(anyhow!("heartbeat is not signed by the signer"));
+        }
+
+        self.heartbeat_pointer().set_value::<u64>(self.height());
+        Ok(response)
+    }
+
+    /// Set the heartbeat staleness threshold (owner only)
+    fn set_heartbeat_threshold(&self, blocks: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.only_owner()?;
+        self.require_not_paused(PauseScope::Admin)?;
+        let context = self.context()?;
+        let response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let old_threshold = self.heartbeat_threshold_pointer().get_value::<u64>();
+        let threshold: u64 = blocks.try_into()?;
+        self.heartbeat_threshold_pointer().set_value::<u64>(threshold);
+        self.heartbeat_threshold_height_pointer()
+            .set_value::<u64>(self.height());
+        self.audit(
+            AdminAction::SetHeartbeatThreshold,
+            old_threshold.to_le_bytes().to_vec(),
+            threshold.to_le_bytes().to_vec(),
+        )?;
+        Ok(response)
+    }
+
+    /// Get the signer's last heartbeat, the threshold and whether it is live
+    fn get_signer_liveness(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        for value in [
+            self.heartbeat_pointer().get_value::<u64>() as u128,
+            self.heartbeat_threshold_pointer().get_value::<u64>() as u128,
+            self.signer_live() as u128,
+        ] {
+            response.data.extend(&value.to_le_bytes());
+        }
+        Ok(response)
+    }
+
+    /// Acknowledge the payments fulfilled by this transaction
+    ///
+    /// A payment is fulfilled when the transaction spends its spendable
+    /// outpoint and has an output paying at least its value to its script.
+    /// Each output fulfills at most one payment. Only the fulfiller may call
+    /// this, and the transaction itself must still prove every payment.
+    fn acknowledge_payments(&self) -> Result<CallResponse> {
+        configure_network()?;
+        self.only_role(Role::Fulfiller)?;
+        let context = self.context()?;
+        let response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+        let mut used = vec![false; tx.output.len()];
+        let mut count = 0;
+        for input in tx.input.iter() {
+            let indices = self.ledger_index_pointer(&input.previous_output)?;
+            for i in 0..indices.length() {
+                let index = indices.select_index(i).get_value::<u32>();
+                let mut flag = self.ledger_acknowledged_pointer(index);
+                if flag.get_value::<u8>() != 0 {
+                    continue;
+                }
+                let payment = self.ledger_payment(index)?;
+                let Some(vout) = tx.output.iter().zip(used.iter()).position(|(output, used)| {
+                    !used
+                        && output.script_pubkey == payment.output.script_pubkey
+                        && output.value >= payment.output.value
+                }) else {
+                    continue;
+                };
+                used[vout] = true;
+                flag.set_value::<u8>(1);
+                count += 1;
+            }
+        }
+        if count == 0 {
+            return Err(anyhow!("transaction fulfills no outstanding payment"));
+        }
+
+        let mut acknowledged = self.acknowledged_pointer();
+        acknowledged.set_value::<u32>(acknowledged.get_value::<u32>() + count);
+        let mut head_ptr = self.ledger_head_pointer();
+        let mut head = head_ptr.get_value::<u32>();
+        while head < self.payment_ledger_pointer().length()
+            && self.ledger_acknowledged_pointer(head).get_value::<u8>() != 0
+        {
+            head += 1;
+        }
+        head_ptr.set_value::<u32>(head);
+        Ok(response)
+    }
+
+    /// Set the payment SLA and overdue backlog threshold (owner only)
+    fn set_payment_sla(&self, blocks: u128, pause_threshold: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.only_owner()?;
+        self.require_not_paused(PauseScope::Admin)?;
+        let context = self.context()?;
+        let response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let old_settings = [
+            (self.payment_sla() as u128).to_le_bytes(),
+            self.delinquency_pause_threshold().to_le_bytes(),
+        ]
+        .concat();
+        StoragePointer::from_keyword("/payments/sla").set_value::<u64>(blocks.try_into()?);
+        StoragePointer::from_keyword("/payments/sla-pause-threshold")
+            .set_value::<u128>(pause_threshold);
+        self.audit(
+            AdminAction::SetPaymentSla,
+            old_settings,
+            [blocks.to_le_bytes(), pause_threshold.to_le_bytes()].concat(),
+        )?;
+        Ok(response)
+    }
+
+    /// Get the overdue payment count and the height of the oldest overdue payment
+    fn get_delinquency(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let (overdue, oldest) = self.overdue_payments();
+        let outstanding = self.payment_ledger_pointer().length()
+            - self.acknowledged_pointer().get_value::<u32>();
+        for value in [
+            overdue as u128,
+            oldest.unwrap_or(0) as u128,
+            outstanding as u128,
+            self.payment_sla() as u128,
+            self.delinquency_pause_threshold(),
+        ] {
+            response.data.extend(&value.to_le_bytes());
+        }
+        Ok(response)
+    }
+
+    /// Enter wind-down mode (owner only)
+    ///
+    /// Only allowed once the signer has failed: a payment is overdue or its
+    /// heartbeat is stale. Reserves and supply are snapshotted so that every holder redeems at
+    /// the same rate no matter when they burn.
+    fn enter_wind_down(&self, vout: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.only_owner()?;
+        self.require_not_paused(PauseScope::Admin)?;
+        self.require_not_winding_down()?;
+        if self.overdue_payments().0 == 0 && self.signer_live() {
+            return Err(anyhow!(
+                "wind-down requires overdue payments or a stale signer heartbeat"
+            ));
+        }
+        let context = self.context()?;
+        let response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+        let recovery = tx
+            .output
+            .get(vout as usize)
+            .ok_or("")
+            .map_err(|_| anyhow!("vout out of range"))?
+            .script_pubkey
+            .clone();
+        let state = self.wind_down_pointer();
+        state.keyword("/active").set_value::<u8>(1);
+        state.keyword("/height").set_value::<u64>(self.height());
+        state.keyword("/reserves").set_value::<u128>(self.reserves());
+        state
+            .keyword("/supply")
+            .set_value::<u128>(self.total_supply() + self.pending_mint_total());
+        state
+            .keyword("/recovery")
+            .set(Arc::new(recovery.as_bytes().to_vec()));
+        self.audit(AdminAction::EnterWindDown, vec![], recovery.as_bytes().to_vec())?;
+        Ok(response)
+    }
+
+    /// Burn synthetic tokens for a pro-rata claim on the reserves snapshotted at wind-down
+    fn redeem(&self) -> Result<CallResponse> {
+        configure_network()?;
+        if !self.winding_down() {
+            return Err(anyhow!("redemptions are only available during wind-down"));
+        }
+        let context = self.context()?;
+
+        if context.caller.clone() != (AlkaneId { tx: 0, block: 0 }) {
+            return Err(anyhow!("must be called by EOA"));
+        }
+        if context.incoming_alkanes.0.len() != 1
+            || context.incoming_alkanes.0[0].id != context.myself
+        {
+            return Err(anyhow!("must only send the synthetic token as input"));
+        }
+
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+        let message = self.protostone(&tx, &context)?;
+        let pointer = message
+            .pointer
+            .ok_or("")
+            .map_err(|_| anyhow!("no pointer in message"))?;
+        if pointer as usize >= tx.output.len() {
+            return Err(anyhow!("pointer cannot be a protomessage"));
+        }
+        let claimant = tx.output[pointer as usize].script_pubkey.clone();
+        if self.is_blocked(&claimant) {
+            return Err(anyhow!("redemption destination is blocked"));
+        }
+
+        let burned: u128 = self.burn_input(&context)?.into();
+        let state = self.wind_down_pointer();
+        let supply = state.keyword("/supply").get_value::<u128>();
+        let amount = if supply == 0 {
+            0
+        } else {
+            burned * state.keyword("/reserves").get_value::<u128>() / supply
+        };
+        let claim = RedemptionClaim {
+            claimant,
+            burned,
+            amount: amount.try_into()?,
+            height: self.height(),
+            txid: tx.compute_txid(),
+        };
+        self.redemption_claims_pointer()
+            .append(Arc::new(claim.serialize()?));
+        self.set_total_supply(self.total_supply().saturating_sub(burned));
+
+        let mut response = CallResponse::default();
+        response.data = amount.to_le_bytes().to_vec();
+        Ok(response)
+    }
+
+    /// Get the wind-down state
+    fn get_wind_down(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let state = self.wind_down_pointer();
+        for value in [
+            self.winding_down() as u128,
+            state.keyword("/height").get_value::<u64>() as u128,
+            state.keyword("/reserves").get_value::<u128>(),
+            state.keyword("/supply").get_value::<u128>(),
+            self.redemption_claims_pointer().length() as u128,
+        ] {
+            response.data.extend(&value.to_le_bytes());
+        }
+        response
+            .data
+            .extend(state.keyword("/recovery").get().as_ref());
+        Ok(response)
+    }
+
+    /// Get a page of redemption claims
+    fn get_redemption_claims(&self, offset: u128, limit: u128) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = self.list_page(&self.redemption_claims_pointer(), offset, limit);
+        Ok(response)
+    }
+
+    /// Set the delayed mint threshold and delay (owner only)
+    fn set_delayed_mint(&self, threshold: u128, delay: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.only_owner()?;
+        self.require_not_paused(PauseScope::Admin)?;
+        let context = self.context()?;
+        let response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let old_settings = [
+            self.delayed_mint_threshold().to_le_bytes(),
+            (self.delayed_mint_delay() as u128).to_le_bytes(),
+        ]
+        .concat();
+        let state = self.delayed_mint_pointer();
+        state.keyword("/threshold").set_value::<u128>(threshold);
+        state.keyword("/delay").set_value::<u64>(delay.try_into()?);
+        self.audit(
+            AdminAction::SetDelayedMint,
+            old_settings,
+            [threshold.to_le_bytes(), delay.to_le_bytes()].concat(),
+        )?;
+        Ok(response)
+    }
+
+    /// Mint every matured delayed wrap whose recipient output is spent by this transaction
+    fn claim(&self) -> Result<CallResponse> {
+        configure_network()?;
+        self.require_not_paused(PauseScope::Wrap)?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+        let mut total: u128 = 0;
+        for input in tx.input.iter() {
+            let mut pending = self.pending_mint_pointer(&input.previous_output)?;
+            let value = pending.get();
+            if value.len() == 0 {
+                continue;
+            }
+            let mut amount = [0u8; 16];
+            amount.copy_from_slice(&value.as_ref()[0..16]);
+            let mut claimable_at = [0u8; 8];
+            claimable_at.copy_from_slice(&value.as_ref()[16..24]);
+            if self.height() < u64::from_le_bytes(claimable_at) {
+                continue;
+            }
+            total += u128::from_le_bytes(amount);
+            pending.set(Arc::new(vec![]));
+        }
+        if total == 0 {
+            return Err(anyhow!("no matured delayed mints are spent by this transaction"));
+        }
+        self.set_pending_mint_total(self.pending_mint_total().saturating_sub(total));
+        response.alkanes.0.push(self.mint(&context, total)?);
+        Ok(response)
+    }
+
+    /// Get the delayed mint policy and the unclaimed total
+    fn get_delayed_mints(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        for value in [
+            self.delayed_mint_threshold(),
+            self.delayed_mint_delay() as u128,
+            self.pending_mint_total(),
+        ] {
+            response.data.extend(&value.to_le_bytes());
+        }
+        Ok(response)
+    }
+
+    /// Record protostone-less deposits made in the current block
+    fn record_deposits(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let recorded = self.record_deposits_internal()?;
+        response.data = (recorded as u128).to_le_bytes().to_vec();
+        Ok(response)
+    }
+
+    /// Record a deposit to a deposit address that was not recorded in its
+    /// own block, as attested by the signer
+    ///
+    /// The recipient is the pointer output's script. The signature covers
+    /// `deposit_attestation_message` of the deposit outpoint, its value and
+    /// the recipient. The funding inputs are not visible, so screening treats
+    /// them as unknown.
+    ///
+    /// # Returns
+    /// The index of the recorded deposit
+    #[allow(clippy::too_many_arguments)]
+    fn attest_deposit(
+        &self,
+        txid_0: u128,
+        txid_1: u128,
+        vout: u128,
+        value: u128,
+        signature_0: u128,
+        signature_1: u128,
+        signature_2: u128,
+        signature_3: u128,
+    ) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+        let recipient = self.pointer_script(&tx, &context)?;
+        let mut txid = [0u8; 32];
+        txid[0..16].copy_from_slice(&txid_0.to_le_bytes());
+        txid[16..32].copy_from_slice(&txid_1.to_le_bytes());
+        let outpoint = OutPoint {
+            txid: Txid::from_byte_array(txid),
+            vout: vout.try_into()?,
+        };
+        let value: u64 = value.try_into()?;
+        if !self.signed_by_signer(
+            [signature_0, signature_1, signature_2, signature_3],
+            &deposit_attestation_message(&outpoint, value, &recipient),
+        )? {
+            return Err(anyhow!("deposit is not attested by the signer"));
+        }
+        self.observe_outpoint(&outpoint)?;
+
+        let deposit = Deposit {
+            claimed: false,
+            outpoint,
+            value,
+            height: self.height(),
+            funding_scripts: vec![ScriptBuf::new()],
+            recipient: Some(recipient),
+        };
+        let mut deposits = self.deposits_pointer();
+        response.data = (deposits.length() as u128).to_le_bytes().to_vec();
+        deposits.append(Arc::new(deposit.serialize()?));
+        Ok(response)
+    }
+
+    /// Mint synthetic tokens for a recorded deposit
+    ///
+    /// A deposit to a deposit address is minted to its committed recipient.
+    /// A plain signer deposit is minted to the pointer output of a
+    /// transaction that spends from one of the scripts that funded it,
+    /// proving the claimer controls that script.
+    fn claim_deposit(&self, index: u128) -> Result<CallResponse> {
+        configure_network()?;
+        self.require_wraps_open()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let mut entry = self.deposits_pointer().select_index(index.try_into()?);
+        let value = entry.get();
+        if value.len() == 0 {
+            return Err(anyhow!("no deposit at index {}", index));
+        }
+        let mut deposit = Deposit::parse(&mut std::io::Cursor::new(value.as_ref().clone()))?;
+        if deposit.claimed {
+            return Err(anyhow!("deposit already claimed"));
+        }
+
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+        match deposit.recipient.as_ref() {
+            // Anyone may claim a deposit address payment, but only to its recipient
+            Some(recipient) => {
+                if self.pointer_script(&tx, &context)? != *recipient {
+                    return Err(anyhow!("deposit must be claimed to its committed recipient"));
+                }
+            }
+            None => {
+                let funded = tx.input.iter().filter_map(spent_script).any(|script| {
+                    deposit
+                        .funding_scripts
+                        .iter()
+                        .any(|funding| !funding.is_empty() && *funding == script)
+                });
+                if !funded {
+                    return Err(anyhow!(
+                        "claim must spend from a script that funded the deposit"
+                    ));
+                }
+            }
+        }
+        deposit.claimed = true;
+        entry.set(Arc::new(deposit.serialize()?));
+
+        // Unrecoverable input scripts are recorded empty
+        let source_blocked = self.sources_blocked(
+            deposit
+                .funding_scripts
+                .iter()
+                .map(|script| (!script.is_empty()).then(|| script.clone())),
+        );
+        response.alkanes.0.push(self.mint_wrapped(
+            &context,
+            &tx,
+            Some(deposit.outpoint),
+            deposit.value.into(),
+            source_blocked,
+        )?);
+        Ok(response)
+    }
+
+    /// Register the deposit address committing to the pointer output's script
+    fn register_deposit_address(&self) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        let tx = consensus_decode::<Transaction>(&mut std::io::Cursor::new(self.transaction()))?;
+        let recipient = self.pointer_script(&tx, &context)?;
+        if self.is_blocked(&recipient) {
+            return Err(anyhow!("deposit recipient is blocked"));
+        }
+        let script = self.recipient_deposit_script(&recipient)?;
+        self.deposit_addresses_pointer()
+            .select(&script.as_bytes().to_vec())
+            .set(Arc::new(recipient.into_bytes()));
+        response.data = script.into_bytes();
+        Ok(response)
+    }
+
+    /// Get a page of recorded deposits
+    fn get_deposits(&self, offset: u128, limit: u128) -> Result<CallResponse> {
+        configure_network()?;
+        let context = self.context()?;
+        let mut response: CallResponse = CallResponse::forward(&context.incoming_alkanes);
+
+        response.data = self.list_page(&self.deposits_pointer(), offset, limit);
+        Ok(response)
+    }
+
+    #[cfg(feature = "test-hooks")]
+    fn set_signer_address_for_test(
+        &self,
+        part1: u128,
+        part2: u128,
+        part3: u128,
+    ) -> Result<CallResponse> {
+        let mut address = [0u8; 32];
+        let p1_bytes = part1.to_le_bytes();
+        let p2_bytes = part2.to_le_bytes();
+        let p3_bytes = part3.to_le_bytes();
+
+        address[0..12].copy_from_slice(&p1_bytes[0..12]);
+        address[12..24].copy_from_slice(&p2_bytes[0..12]);
+        address[24..32].copy_from_slice(&p3_bytes[0..8]);
+
+        self.signer_pointer().set(Arc::new(address.to_vec()));
+        Ok(CallResponse::default())
+    }
+}
+
+#[doc(hidden)]
+pub mod __macro_support {
+    pub use crate::SyntheticAsset;
+    pub use alkanes_runtime::auth::AuthenticatedResponder;
+    pub use alkanes_runtime::{
+        declare_alkane, message::MessageDispatch, runtime::AlkaneResponder, storage::StoragePointer,
+    };
+    pub use alkanes_runtime::{
+        println,
+        stdio::{stdout, Write},
+    };
+    pub use alkanes_std_factory_support::MintableToken;
+    pub use alkanes_support::id::AlkaneId;
+    pub use alkanes_support::{context::Context, parcel::AlkaneTransfer, response::CallResponse};
+    pub use anyhow::{anyhow, Result};
+    pub use fr_btc_support::{Network, CONFIGURED_NETWORK};
+    pub use metashrew_support::compat::{to_arraybuffer_layout, to_passback_ptr};
+    pub use metashrew_support::index_pointer::KeyValuePointer;
+    pub use std::sync::Arc;
+
+    /// Check whether `network` is one of `networks`, in a const context
+    pub const fn supports(networks: &[Network], network: Network) -> bool {
+        let mut i = 0;
+        while i < networks.len() {
+            if networks[i] as u8 == network as u8 {
+                return true;
+            }
+            i += 1;
+        }
+        false
+    }
+}
+
+/// Declare the message enum and alkane entrypoint of a synthetic asset
+///
+/// `$asset` must implement [`SyntheticAsset`]; `$message` names the generated
+/// message enum and must be `$asset` followed by `Message`.
+#[macro_export]
+macro_rules! declare_synthetic_asset {
+    ($asset:ident, $message:ident) => {
+        mod __synthetic_asset {
+            #[allow(unused_imports)]
+            use $crate::__macro_support::*;
+            // Named so they shadow the std prelude macros rather than clash with them
+            #[allow(unused_imports)]
+            use $crate::__macro_support::{println, stdout, Write};
+            use super::$asset;
+
+            // A build for a network the asset cannot be deployed to is useless
+            const _: () = assert!(
+                supports(<$asset as SyntheticAsset>::NETWORKS, CONFIGURED_NETWORK),
+                "the build's network feature is not in the asset's NETWORKS"
+            );
+
+            /// Message enum for opcode-based dispatch
+            #[derive(MessageDispatch)]
+            pub enum $message {
+                /// Initialize the contract for a network, returning a newly created owner
+                /// auth token to the caller
+                #[opcode(0)]
+                Initialize {
+                    /// Network identifier (see `fr_btc_support::Network`)
+                    network: u128,
+                },
+
+                /// Set the signer script pubkey
+                #[opcode(1)]
+                SetSigner {
+                    /// Output index in the transaction
+                    vout: u128,
+                },
+
+                /// Set the premium value (owner only)
+                #[opcode(4)]
+                SetPremium {
+                    /// Premium value (0-100000000)
+                    premium: u128,
+                },
+
+                /// Bind the guardian role to an auth token (owner only)
+                #[opcode(5)]
+                SetGuardian {
+                    /// Block of the guardian's auth token
+                    block: u128,
+                    /// Tx of the guardian's auth token
+                    tx: u128,
+                },
+
+                /// Pause wrap, unwrap and/or admin operations (guardian or owner)
+                #[opcode(6)]
+                Pause {
+                    /// Nonzero to pause wraps
+                    wrap: u128,
+                    /// Nonzero to pause unwraps
+                    unwrap: u128,
+                    /// Nonzero to pause admin operations
+                    admin: u128,
+                },
+
+                /// Lift pauses on wrap, unwrap and/or admin operations (owner only)
+                #[opcode(7)]
+                Unpause {
+                    /// Nonzero to unpause wraps
+                    wrap: u128,
+                    /// Nonzero to unpause unwraps
+                    unwrap: u128,
+                    /// Nonzero to unpause admin operations
+                    admin: u128,
+                },
+
+                /// Remove a role binding so that only the owner holds it (owner only)
+                #[opcode(8)]
+                RevokeRole {
+                    /// Role identifier (see `Role`)
+                    role: u128,
+                },
+
+                /// Move ownership to a new auth token, which must be sent in alongside the current one (owner only)
+                #[opcode(9)]
+                MigrateAuthToken {
+                    /// Block of the new auth token
+                    block: u128,
+                    /// Tx of the new auth token
+                    tx: u128,
+                },
+
+                /// Set the maximum total supply, 0 for uncapped (owner only)
+                #[opcode(10)]
+                SetSupplyCap {
+                    /// Maximum total supply in satoshis
+                    cap: u128,
+                },
+
+                /// Set per-window limits on sats wrapped and unwrapped, 0 for unlimited (owner only)
+                #[opcode(11)]
+                SetRateLimits {
+                    /// Window length in blocks
+                    window: u128,
+                    /// Maximum sats wrapped per window
+                    wrap_limit: u128,
+                    /// Maximum sats unwrapped per window
+                    unwrap_limit: u128,
+                },
+
+                /// Move queued unwraps into the payment queue as far as the unwrap limit allows
+                #[opcode(12)]
+                ReleaseQueuedUnwraps,
+
+                /// Block or unblock the script pubkey of an output in this transaction (compliance-admin)
+                #[opcode(13)]
+                SetScriptBlocked {
+                    /// Output index in the transaction carrying the script
+                    vout: u128,
+                    /// Nonzero to block, zero to unblock
+                    blocked: u128,
+                },
+
+                /// Enable or disable screening of wrap funding inputs against the blocklist (compliance-admin)
+                #[opcode(14)]
+                SetWrapScreening {
+                    /// Nonzero to screen wrap inputs
+                    enabled: u128,
+                },
+
+                /// Record that the signer is alive at the current height
+                #[opcode(15)]
+                Heartbeat {
+                    /// Bytes 0..16 of the signer's signature over the first input
+                    signature_0: u128,
+                    /// Bytes 16..32 of the signature
+                    signature_1: u128,
+                    /// Bytes 32..48 of the signature
+                    signature_2: u128,
+                    /// Bytes 48..64 of the signature
+                    signature_3: u128,
+                },
+
+                /// Set how many blocks without a heartbeat suspend wraps, 0 to disable (owner only)
+                #[opcode(16)]
+                SetHeartbeatThreshold {
+                    /// Maximum blocks since the last heartbeat
+                    blocks: u128,
+                },
+
+                /// Mark the payments this transaction fulfills as paid (fulfiller only)
+                #[opcode(17)]
+                AcknowledgePayments,
+
+                /// Set the payment SLA and the overdue backlog that suspends wraps, 0 to disable either (owner only)
+                #[opcode(18)]
+                SetPaymentSla {
+                    /// Blocks a payment may stay unfulfilled before it is overdue
+                    blocks: u128,
+                    /// Overdue payment count above which wraps are suspended
+                    pause_threshold: u128,
+                },
+
+                /// Stop wraps and unwraps and let holders redeem pro-rata claims on the
+                /// reserves, paid from the recovery script at `vout`, once a payment is overdue or the
+                /// signer heartbeat is stale (owner only)
+                #[opcode(19)]
+                EnterWindDown {
+                    /// Output index in the transaction carrying the recovery script
+                    vout: u128,
+                },
+
+                /// Burn synthetic tokens for a pro-rata claim on the reserves during wind-down
+                #[opcode(20)]
+                Redeem,
+
+                /// Delay minting for wraps of at least `threshold` by `delay` blocks, 0 to disable (owner only)
+                #[opcode(21)]
+                SetDelayedMint {
+                    /// Smallest synthetic token amount whose mint is delayed
+                    threshold: u128,
+                    /// Blocks to wait before a delayed mint can be claimed
+                    delay: u128,
+                },
+
+                /// Mint matured delayed wraps whose recipient outputs this transaction spends
+                #[opcode(22)]
+                Claim,
+
+                /// Record payments to registered deposit addresses in the current block as claimable deposits
+                #[opcode(23)]
+                RecordDeposits,
+
+                /// Mint synthetic tokens for a recorded deposit to the recipient its deposit address commits to
+                #[opcode(24)]
+                ClaimDeposit {
+                    /// Index of the deposit
+                    index: u128,
+                },
+
+                /// Register a reusable deposit address committing to the pointer output's
+                /// script; returns the deposit script pubkey
+                #[opcode(25)]
+                #[returns(Vec<u8>)]
+                RegisterDepositAddress,
+
+                /// Override the network's address parameters, or restore its own with
+                /// a zero bech32 prefix (owner only)
+                #[opcode(26)]
+                SetNetworkParams {
+                    /// Bech32 human-readable prefix, packed little-endian
+                    bech32_prefix: u128,
+                    /// P2PKH address version byte
+                    p2pkh_prefix: u128,
+                    /// P2SH address version byte
+                    p2sh_prefix: u128,
+                },
+
+                /// Bind a role to an auth token (owner only)
+                #[opcode(27)]
+                GrantRole {
+                    /// Role identifier (see `Role`)
+                    role: u128,
+                    /// Block of the role's auth token
+                    block: u128,
+                    /// Tx of the role's auth token
+                    tx: u128,
+                },
+
+                /// Record a deposit to the pointer output's deposit address that was missed in its
+                /// own block, attested by the signer; returns the deposit index
+                #[opcode(28)]
+                #[returns(u128)]
+                AttestDeposit {
+                    /// Bytes 0..16 of the deposit txid, in consensus byte order
+                    txid_0: u128,
+                    /// Bytes 16..32 of the deposit txid
+                    txid_1: u128,
+                    /// Output index of the deposit
+                    vout: u128,
+                    /// Value of the deposit in sats
+                    value: u128,
+                    /// Bytes 0..16 of the signer's signature over the deposit
+                    signature_0: u128,
+                    /// Bytes 16..32 of the signature
+                    signature_1: u128,
+                    /// Bytes 32..48 of the signature
+                    signature_2: u128,
+                    /// Bytes 48..64 of the signature
+                    signature_3: u128,
+                },
+
+                /// Wrap coins to synthetic tokens, claiming every unclaimed signer output in the transaction
+                #[opcode(77)]
+                Wrap,
+
+                /// Unwrap synthetic tokens to coins
+                #[opcode(78)]
+                Unwrap {
+                    /// Index of the signer output the payment is spendable from
+                    vout: u128,
+                },
+
+                /// Wrap coins to synthetic tokens, claiming only the selected signer outputs
+                #[opcode(79)]
+                WrapOutputs {
+                    /// Bitmask of output indices to claim (bit n selects output n)
+                    outputs: u128,
+                },
+
+                /// Get token name
+                #[opcode(99)]
+                #[returns(String)]
+                GetName,
+
+                /// Get token symbol
+                #[opcode(100)]
+                #[returns(String)]
+                GetSymbol,
+
+                /// Get pending payments
+                #[opcode(101)]
+                #[returns(Vec<u8>)]
+                GetPendingPayments,
+
+                /// Get token decimals
+                #[opcode(102)]
+                #[returns(u8)]
+                GetDecimals,
+
+                /// Get the signer address
+                #[opcode(103)]
+                #[returns(Vec<u8>)]
+                GetSigner,
+
+                /// Get the current premium value
+                #[opcode(104)]
+                #[returns(u128)]
+                GetPremium,
+
+                /// Get total supply
+                #[opcode(105)]
+                #[returns(u128)]
+                GetTotalSupply,
+
+                /// Get the pause flags as three bytes: [wrap, unwrap, admin]
+                #[opcode(106)]
+                #[returns(Vec<u8>)]
+                GetPauseState,
+
+                /// Get the current role holders as (role, block, tx) u128 triples
+                #[opcode(107)]
+                #[returns(Vec<u8>)]
+                GetRoles,
+
+                /// Get the maximum total supply (0 when uncapped)
+                #[opcode(108)]
+                #[returns(u128)]
+                GetSupplyCap,
+
+                /// Get the rate limits and current usage as u128s: [window, wrap_limit,
+                /// unwrap_limit, wrapped, unwrapped, queued_unwraps]
+                #[opcode(109)]
+                #[returns(Vec<u8>)]
+                GetRateLimits,
+
+                /// Get the blocked script pubkeys, each prefixed with its u32 length
+                #[opcode(110)]
+                #[returns(Vec<u8>)]
+                GetBlockedScripts,
+
+                /// Get a page of the admin audit log: the total entry count (u128)
+                /// followed by up to `limit` serialized `AuditEntry`s from `offset`
+                #[opcode(111)]
+                #[returns(Vec<u8>)]
+                GetAuditLog {
+                    /// Index of the first entry to return
+                    offset: u128,
+                    /// Maximum number of entries to return
+                    limit: u128,
+                },
+
+                /// Get signer liveness as u128s: [last_heartbeat_height, threshold, live]
+                #[opcode(112)]
+                #[returns(Vec<u8>)]
+                GetSignerLiveness,
+
+                /// Get overdue payments as u128s: [overdue_count, oldest_overdue_height,
+                /// outstanding_count, sla_blocks, pause_threshold]
+                #[opcode(113)]
+                #[returns(Vec<u8>)]
+                GetDelinquency,
+
+                /// Get the wind-down state as u128s: [active, height, reserves, supply,
+                /// claim_count], followed by the recovery script
+                #[opcode(114)]
+                #[returns(Vec<u8>)]
+                GetWindDown,
+
+                /// Get a page of redemption claims: the total claim count (u128)
+                /// followed by up to `limit` serialized `RedemptionClaim`s from `offset`
+                #[opcode(115)]
+                #[returns(Vec<u8>)]
+                GetRedemptionClaims {
+                    /// Index of the first claim to return
+                    offset: u128,
+                    /// Maximum number of claims to return
+                    limit: u128,
+                },
+
+                /// Get the delayed mint policy and backlog as u128s: [threshold, delay, pending_total]
+                #[opcode(116)]
+                #[returns(Vec<u8>)]
+                GetDelayedMints,
+
+                /// Get a page of recorded deposits: the total deposit count (u128)
+                /// followed by up to `limit` serialized `Deposit`s from `offset`
+                #[opcode(117)]
+                #[returns(Vec<u8>)]
+                GetDeposits {
+                    /// Index of the first deposit to return
+                    offset: u128,
+                    /// Maximum number of deposits to return
+                    limit: u128,
+                },
+
+                /// Get a JSON description of every opcode, its inputs and return type
+                #[opcode(118)]
+                #[returns(String)]
+                GetAbi,
+
+                /// Get the token metadata, signer, pause state, auth token, pending
+                /// payment count and reserves as a versioned `ContractInfo`
+                #[opcode(119)]
+                #[returns(Vec<u8>)]
+                GetInfo,
+
+                /// Get the total supply as of the end of a block
+                #[opcode(120)]
+                #[returns(u128)]
+                GetTotalSupplyAt {
+                    /// Block height
+                    height: u128,
+                },
+
+                /// Get the premium as of the end of a block
+                #[opcode(121)]
+                #[returns(u128)]
+                GetPremiumAt {
+                    /// Block height
+                    height: u128,
+                },
+
+                /// Get cumulative statistics as u128s: wrap count, unwrap count, sats
+                /// wrapped, sats unwrapped, fees and unique unwrap destinations
+                #[opcode(122)]
+                #[returns(Vec<u8>)]
+                GetStats,
+
+                /// Get the synthetic tokens a wrap sending `amount` sats to the signer would mint,
+                /// and the height they can be claimed at, as u128s: [minted, mintable_at]
+                #[opcode(123)]
+                #[returns(Vec<u8>)]
+                QuoteWrap {
+                    /// Sats sent to the signer
+                    amount: u128,
+                },
+
+                /// Get the coins an unwrap burning `amount` synthetic tokens would pay out
+                #[opcode(124)]
+                #[returns(u128)]
+                QuoteUnwrap {
+                    /// Synthetic tokens burned
+                    amount: u128,
+                },
+
+                /// Get the network identifier chosen at initialization
+                #[opcode(125)]
+                #[returns(u8)]
+                GetNetwork,
+
+                /// Get the address parameters in effect as a serialized `AddressParams`
+                #[opcode(126)]
+                #[returns(Vec<u8>)]
+                GetNetworkParams,
+
+                /// Set the signer address for testing purposes
+                #[cfg(feature = "test-hooks")]
+                #[opcode(666)]
+                SetSignerAddressForTest {
+                    part1: u128,
+                    part2: u128,
+                    part3: u128,
+                },
+            }
+
+            impl $asset {
+                /// Get the contract ABI as JSON, generated from the message enum
+                fn get_abi(&self) -> Result<CallResponse> {
+                    let context = self.context()?;
+                    let mut response = CallResponse::forward(&context.incoming_alkanes);
+                    response.data = <$message as MessageDispatch<$asset>>::export_abi();
+                    Ok(response)
+                }
+            }
+
+            impl MintableToken for $asset {
+                /// Store the total supply and checkpoint it at the current height
+                fn set_total_supply(&self, v: u128) {
+                    self.total_supply_pointer().set_value::<u128>(v);
+                    SyntheticAsset::checkpoint(self, self.supply_checkpoints_pointer(), v);
+                }
+            }
+
+            impl AlkaneResponder for $asset {}
+
+            impl AuthenticatedResponder for $asset {}
+
+            declare_alkane! {
+                impl AlkaneResponder for $asset {
+                    type Message = $message;
+                }
+            }
+        }
+
+        pub use __synthetic_asset::$message;
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_params() {
+        for (network, bech32_prefix, p2pkh_prefix, p2sh_prefix) in [
+            (Network::Regtest, "bcrt", 0x64, 0xc4),
+            (Network::Testnet, "tb", 0x6f, 0xc4),
+            (Network::Signet, "tb", 0x6f, 0xc4),
+            (Network::Mainnet, "bc", 0x00, 0x05),
+            (Network::Fractal, "bc", 0x00, 0x05),
+            (Network::Dogecoin, "dc", 0x1e, 0x16),
+            (Network::Luckycoin, "lky", 0x2f, 0x05),
+            (Network::Bellscoin, "bel", 0x19, 0x1e),
+        ] {
+            let params = network_params(network);
+            assert_eq!(params.bech32_prefix, bech32_prefix);
+            assert_eq!(params.p2pkh_prefix, p2pkh_prefix);
+            assert_eq!(params.p2sh_prefix, p2sh_prefix);
+        }
+    }
+
+    #[test]
+    fn test_uninitialized_network_is_build_default() {
+        assert_eq!(network().unwrap(), CONFIGURED_NETWORK);
+    }
+
+    #[test]
+    fn test_unpack_bech32_prefix() {
+        let pack = |prefix: &[u8]| {
+            let mut bytes = [0u8; 16];
+            bytes[..prefix.len()].copy_from_slice(prefix);
+            u128::from_le_bytes(bytes)
+        };
+        assert_eq!(unpack_bech32_prefix(pack(b"tb")).unwrap(), "tb");
+        assert_eq!(unpack_bech32_prefix(pack(b"bcrt")).unwrap(), "bcrt");
+        assert!(unpack_bech32_prefix(0).is_err());
+        assert!(unpack_bech32_prefix(pack(b"TB")).is_err());
+    }
+
+    #[test]
+    fn test_lock_scripts() {
+        let key = Network::Mainnet.default_signer().unwrap();
+        assert!(p2tr_lock_script(key).is_p2tr());
+        assert!(p2pkh_lock_script(key).is_p2pkh());
+    }
+
+    #[test]
+    fn test_p2pkh_lock_script_uses_even_parity() {
+        let secp = secp256k1::Secp256k1::new();
+        let secret = (1..=u8::MAX)
+            .map(|b| secp256k1::SecretKey::from_slice(&[b; 32]).unwrap())
+            .find(|secret| secret.x_only_public_key(&secp).1 == Parity::Odd)
+            .unwrap();
+        let (key, _) = secret.x_only_public_key(&secp);
+        let pubkey_hash = |secret: secp256k1::SecretKey| {
+            PublicKey::new(secret.public_key(&secp)).pubkey_hash()
+        };
+
+        assert_ne!(
+            p2pkh_lock_script(key),
+            ScriptBuf::new_p2pkh(&pubkey_hash(secret))
+        );
+        assert_eq!(
+            p2pkh_lock_script(key),
+            ScriptBuf::new_p2pkh(&pubkey_hash(secret.negate()))
+        );
+    }
+}
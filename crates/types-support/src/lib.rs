@@ -1,7 +1,8 @@
-use anyhow::Result;
-use bitcoin::{OutPoint, TxOut};
+use alkanes_support::id::AlkaneId;
+use anyhow::{anyhow, Result};
+use bitcoin::{OutPoint, ScriptBuf, TxOut, Txid};
 use metashrew_support::utils::{consensus_decode, consensus_encode, is_empty};
-use std::io::Cursor;
+use std::io::{Cursor, Read};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Payment {
@@ -32,3 +33,374 @@ pub fn deserialize_payments(v: &Vec<u8>) -> Result<Vec<Payment>> {
     }
     Ok(payments)
 }
+
+/// Admin operations recorded in the frBTC audit log
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AdminAction {
+    Initialize = 0,
+    SetSigner = 1,
+    SetPremium = 2,
+    GrantRole = 3,
+    RevokeRole = 4,
+    Pause = 5,
+    Unpause = 6,
+    MigrateAuthToken = 7,
+    SetSupplyCap = 8,
+    SetRateLimits = 9,
+    SetScriptBlocked = 10,
+    SetWrapScreening = 11,
+    SetHeartbeatThreshold = 12,
+    SetPaymentSla = 13,
+    EnterWindDown = 14,
+    SetDelayedMint = 15,
+    SetNetworkParams = 16,
+}
+
+impl TryFrom<u8> for AdminAction {
+    type Error = anyhow::Error;
+
+    fn try_from(v: u8) -> Result<Self> {
+        Ok(match v {
+            0 => AdminAction::Initialize,
+            1 => AdminAction::SetSigner,
+            2 => AdminAction::SetPremium,
+            3 => AdminAction::GrantRole,
+            4 => AdminAction::RevokeRole,
+            5 => AdminAction::Pause,
+            6 => AdminAction::Unpause,
+            7 => AdminAction::MigrateAuthToken,
+            8 => AdminAction::SetSupplyCap,
+            9 => AdminAction::SetRateLimits,
+            10 => AdminAction::SetScriptBlocked,
+            11 => AdminAction::SetWrapScreening,
+            12 => AdminAction::SetHeartbeatThreshold,
+            13 => AdminAction::SetPaymentSla,
+            14 => AdminAction::EnterWindDown,
+            15 => AdminAction::SetDelayedMint,
+            16 => AdminAction::SetNetworkParams,
+            _ => return Err(anyhow!("unknown admin action {}", v)),
+        })
+    }
+}
+
+/// A single entry in the frBTC admin audit log
+///
+/// `old_value` and `new_value` hold the raw bytes of the setting before and
+/// after the action, in the same layout the matching view opcode returns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub action: AdminAction,
+    pub old_value: Vec<u8>,
+    pub new_value: Vec<u8>,
+    pub height: u64,
+    pub txid: Txid,
+}
+
+fn read_bytes(cursor: &mut Cursor<Vec<u8>>, len: usize) -> Result<Vec<u8>> {
+    let mut buf = vec![0u8; len];
+    cursor.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_u32(cursor: &mut Cursor<Vec<u8>>) -> Result<u32> {
+    Ok(u32::from_le_bytes(read_bytes(cursor, 4)?.try_into().unwrap()))
+}
+
+fn read_u64(cursor: &mut Cursor<Vec<u8>>) -> Result<u64> {
+    Ok(u64::from_le_bytes(read_bytes(cursor, 8)?.try_into().unwrap()))
+}
+
+fn read_u128(cursor: &mut Cursor<Vec<u8>>) -> Result<u128> {
+    Ok(u128::from_le_bytes(read_bytes(cursor, 16)?.try_into().unwrap()))
+}
+
+impl AuditEntry {
+    /// Layout: action (u8), height (u64 LE), txid (32 bytes), then the old
+    /// and new values, each prefixed with a u32 LE length
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        let mut result: Vec<u8> = vec![self.action as u8];
+        result.extend(&self.height.to_le_bytes());
+        result.extend(&consensus_encode::<Txid>(&self.txid)?);
+        result.extend(&(self.old_value.len() as u32).to_le_bytes());
+        result.extend(&self.old_value);
+        result.extend(&(self.new_value.len() as u32).to_le_bytes());
+        result.extend(&self.new_value);
+        Ok(result)
+    }
+
+    pub fn parse(cursor: &mut Cursor<Vec<u8>>) -> Result<AuditEntry> {
+        let action = AdminAction::try_from(read_bytes(cursor, 1)?[0])?;
+        let height = read_u64(cursor)?;
+        let txid = consensus_decode::<Txid>(cursor)?;
+        let old_len = read_u32(cursor)? as usize;
+        let old_value = read_bytes(cursor, old_len)?;
+        let new_len = read_u32(cursor)? as usize;
+        let new_value = read_bytes(cursor, new_len)?;
+        Ok(AuditEntry {
+            action,
+            old_value,
+            new_value,
+            height,
+            txid,
+        })
+    }
+}
+
+pub fn deserialize_audit_entries(v: &Vec<u8>) -> Result<Vec<AuditEntry>> {
+    let mut entries: Vec<AuditEntry> = vec![];
+    let mut cursor: Cursor<Vec<u8>> = Cursor::new(v.clone());
+    while !is_empty(&mut cursor) {
+        entries.push(AuditEntry::parse(&mut cursor)?);
+    }
+    Ok(entries)
+}
+
+/// A holder's pro-rata claim on the reserves, registered by burning frBTC
+/// while the contract is winding down
+#[derive(Debug, Clone, PartialEq)]
+pub struct RedemptionClaim {
+    pub claimant: ScriptBuf,
+    pub burned: u128,
+    pub amount: u64,
+    pub height: u64,
+    pub txid: Txid,
+}
+
+impl RedemptionClaim {
+    /// Layout: txid (32 bytes), height (u64 LE), burned (u128 LE), amount in
+    /// sats (u64 LE), then the claimant script prefixed with its u32 LE length
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        let mut result: Vec<u8> = consensus_encode::<Txid>(&self.txid)?;
+        result.extend(&self.height.to_le_bytes());
+        result.extend(&self.burned.to_le_bytes());
+        result.extend(&self.amount.to_le_bytes());
+        result.extend(&(self.claimant.len() as u32).to_le_bytes());
+        result.extend(self.claimant.as_bytes());
+        Ok(result)
+    }
+
+    pub fn parse(cursor: &mut Cursor<Vec<u8>>) -> Result<RedemptionClaim> {
+        let txid = consensus_decode::<Txid>(cursor)?;
+        let height = read_u64(cursor)?;
+        let burned = read_u128(cursor)?;
+        let amount = read_u64(cursor)?;
+        let script_len = read_u32(cursor)? as usize;
+        let claimant = ScriptBuf::from_bytes(read_bytes(cursor, script_len)?);
+        Ok(RedemptionClaim {
+            claimant,
+            burned,
+            amount,
+            height,
+            txid,
+        })
+    }
+}
+
+pub fn deserialize_redemption_claims(v: &Vec<u8>) -> Result<Vec<RedemptionClaim>> {
+    let mut claims: Vec<RedemptionClaim> = vec![];
+    let mut cursor: Cursor<Vec<u8>> = Cursor::new(v.clone());
+    while !is_empty(&mut cursor) {
+        claims.push(RedemptionClaim::parse(&mut cursor)?);
+    }
+    Ok(claims)
+}
+
+/// BTC sent to the signer without a protostone, recorded so the depositor
+/// can claim frBTC for it later
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deposit {
+    pub claimed: bool,
+    pub outpoint: OutPoint,
+    pub value: u64,
+    pub height: u64,
+    /// Script spent by each input of the deposit transaction, empty where
+    /// the input does not reveal it
+    pub funding_scripts: Vec<ScriptBuf>,
+    /// Recipient committed to by a per-recipient deposit address, if the
+    /// deposit was paid to one
+    pub recipient: Option<ScriptBuf>,
+}
+
+impl Deposit {
+    /// Layout: claimed (u8), outpoint (36 bytes), value (u64 LE), height
+    /// (u64 LE), funding script count (u32 LE), each funding script
+    /// prefixed with its u32 LE length, then the recipient script prefixed
+    /// with its u32 LE length (0 when there is none)
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        let mut result: Vec<u8> = vec![self.claimed as u8];
+        result.extend(&consensus_encode::<OutPoint>(&self.outpoint)?);
+        result.extend(&self.value.to_le_bytes());
+        result.extend(&self.height.to_le_bytes());
+        result.extend(&(self.funding_scripts.len() as u32).to_le_bytes());
+        for script in self.funding_scripts.iter() {
+            result.extend(&(script.len() as u32).to_le_bytes());
+            result.extend(script.as_bytes());
+        }
+        let recipient = self.recipient.as_ref().map(|s| s.as_bytes()).unwrap_or(&[]);
+        result.extend(&(recipient.len() as u32).to_le_bytes());
+        result.extend(recipient);
+        Ok(result)
+    }
+
+    pub fn parse(cursor: &mut Cursor<Vec<u8>>) -> Result<Deposit> {
+        let claimed = read_bytes(cursor, 1)?[0] != 0;
+        let outpoint = consensus_decode::<OutPoint>(cursor)?;
+        let value = read_u64(cursor)?;
+        let height = read_u64(cursor)?;
+        let count = read_u32(cursor)?;
+        let mut funding_scripts = vec![];
+        for _ in 0..count {
+            let len = read_u32(cursor)? as usize;
+            funding_scripts.push(ScriptBuf::from_bytes(read_bytes(cursor, len)?));
+        }
+        let len = read_u32(cursor)? as usize;
+        let recipient = if len == 0 {
+            None
+        } else {
+            Some(ScriptBuf::from_bytes(read_bytes(cursor, len)?))
+        };
+        Ok(Deposit {
+            claimed,
+            outpoint,
+            value,
+            height,
+            funding_scripts,
+            recipient,
+        })
+    }
+}
+
+pub fn deserialize_deposits(v: &Vec<u8>) -> Result<Vec<Deposit>> {
+    let mut deposits: Vec<Deposit> = vec![];
+    let mut cursor: Cursor<Vec<u8>> = Cursor::new(v.clone());
+    while !is_empty(&mut cursor) {
+        deposits.push(Deposit::parse(&mut cursor)?);
+    }
+    Ok(deposits)
+}
+
+/// Layout version of the `GetInfo` response produced by this crate
+pub const CONTRACT_INFO_VERSION: u8 = 1;
+
+/// Aggregate frBTC state returned by the `GetInfo` view
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractInfo {
+    pub version: u8,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub total_supply: u128,
+    pub premium: u128,
+    pub signer: Vec<u8>,
+    /// Pause flags as [wrap, unwrap, admin]
+    pub paused: [bool; 3],
+    pub signer_script: ScriptBuf,
+    pub auth_token: AlkaneId,
+    pub pending_payments: u128,
+    pub reserves: u128,
+}
+
+impl ContractInfo {
+    /// Layout (version 1): version (u8), name and symbol (each prefixed with
+    /// a u32 LE length), decimals (u8), total supply (u128 LE), premium (u128
+    /// LE), signer pubkey (u32 LE length prefix), pause flags (3 x u8), signer
+    /// script (u32 LE length prefix), auth token block and tx (2 x u128 LE),
+    /// pending payment count (u128 LE), reserves (u128 LE)
+    ///
+    /// Later versions only append fields, so this decoder reads their
+    /// version 1 prefix.
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        let mut result: Vec<u8> = vec![self.version];
+        for bytes in [self.name.as_bytes(), self.symbol.as_bytes()] {
+            result.extend(&(bytes.len() as u32).to_le_bytes());
+            result.extend(bytes);
+        }
+        result.push(self.decimals);
+        result.extend(&self.total_supply.to_le_bytes());
+        result.extend(&self.premium.to_le_bytes());
+        result.extend(&(self.signer.len() as u32).to_le_bytes());
+        result.extend(&self.signer);
+        result.extend(self.paused.iter().map(|paused| *paused as u8));
+        result.extend(&(self.signer_script.len() as u32).to_le_bytes());
+        result.extend(self.signer_script.as_bytes());
+        result.extend(&self.auth_token.block.to_le_bytes());
+        result.extend(&self.auth_token.tx.to_le_bytes());
+        result.extend(&self.pending_payments.to_le_bytes());
+        result.extend(&self.reserves.to_le_bytes());
+        Ok(result)
+    }
+
+    pub fn parse(cursor: &mut Cursor<Vec<u8>>) -> Result<ContractInfo> {
+        let version = read_bytes(cursor, 1)?[0];
+        // Fields appended by later versions are left unread
+        if version == 0 {
+            return Err(anyhow!("unsupported contract info version {}", version));
+        }
+        let len = read_u32(cursor)? as usize;
+        let name = String::from_utf8(read_bytes(cursor, len)?)?;
+        let len = read_u32(cursor)? as usize;
+        let symbol = String::from_utf8(read_bytes(cursor, len)?)?;
+        let decimals = read_bytes(cursor, 1)?[0];
+        let total_supply = read_u128(cursor)?;
+        let premium = read_u128(cursor)?;
+        let len = read_u32(cursor)? as usize;
+        let signer = read_bytes(cursor, len)?;
+        let flags = read_bytes(cursor, 3)?;
+        let len = read_u32(cursor)? as usize;
+        let signer_script = ScriptBuf::from_bytes(read_bytes(cursor, len)?);
+        let auth_token = AlkaneId {
+            block: read_u128(cursor)?,
+            tx: read_u128(cursor)?,
+        };
+        let pending_payments = read_u128(cursor)?;
+        let reserves = read_u128(cursor)?;
+        Ok(ContractInfo {
+            version,
+            name,
+            symbol,
+            decimals,
+            total_supply,
+            premium,
+            signer,
+            paused: [flags[0] != 0, flags[1] != 0, flags[2] != 0],
+            signer_script,
+            auth_token,
+            pending_payments,
+            reserves,
+        })
+    }
+}
+
+pub fn deserialize_contract_info(v: &Vec<u8>) -> Result<ContractInfo> {
+    ContractInfo::parse(&mut Cursor::new(v.clone()))
+}
+
+/// Address parameters of the network a contract serves
+///
+/// Serialized as the P2PKH version byte, the P2SH version byte, then the
+/// bech32 human-readable prefix filling the rest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddressParams {
+    pub bech32_prefix: String,
+    pub p2pkh_prefix: u8,
+    pub p2sh_prefix: u8,
+}
+
+impl AddressParams {
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut result: Vec<u8> = vec![self.p2pkh_prefix, self.p2sh_prefix];
+        result.extend(self.bech32_prefix.as_bytes());
+        result
+    }
+}
+
+pub fn deserialize_address_params(v: &Vec<u8>) -> Result<AddressParams> {
+    if v.len() < 3 {
+        return Err(anyhow!("address params too short"));
+    }
+    Ok(AddressParams {
+        bech32_prefix: String::from_utf8(v[2..].to_vec())?,
+        p2pkh_prefix: v[0],
+        p2sh_prefix: v[1],
+    })
+}
@@ -1,3 +1,4 @@
+use crate::tests::helpers::get_test_address;
 use crate::tests::std::fr_btc_build;
 use alkanes::message::AlkaneMessageContext;
 use alkanes::precompiled::alkanes_std_auth_token_build;
@@ -7,14 +8,16 @@ use alkanes_support::gz::compress;
 use alkanes_support::id::AlkaneId;
 use alkanes_support::response::ExtendedCallResponse;
 use alkanes_support::trace::Trace;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use bitcoin::address::NetworkChecked;
 use bitcoin::blockdata::transaction::OutPoint;
+use bitcoin::hashes::Hash;
 use bitcoin::key::TapTweak;
 use bitcoin::transaction::Version;
 use bitcoin::{
     secp256k1::{self, Secp256k1, XOnlyPublicKey},
-    Address, Amount, Block, Script, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
+    Address, Amount, CompressedPublicKey, Script, ScriptBuf, Sequence, Transaction, TxIn, TxOut,
+    Witness,
 };
 #[allow(unused_imports)]
 use hex;
@@ -29,8 +32,9 @@ use protorune::{
     balance_sheet::load_sheet, message::MessageContext, tables::RuneTable,
     test_helpers::get_address,
 };
-use protorune_support::balance_sheet::{BalanceSheet, BalanceSheetOperations, ProtoruneRuneId};
-use protorune_support::protostone::Protostone;
+use protorune_support::balance_sheet::ProtoruneRuneId;
+use protorune_support::balance_sheet::{BalanceSheet, BalanceSheetOperations};
+use protorune_support::protostone::{Protostone, ProtostoneEdict};
 
 use protorune_support::utils::consensus_encode;
 
@@ -41,23 +45,41 @@ use alkanes::tests::helpers::{
     self as alkane_helpers, assert_return_context, assert_revert_context, get_last_outpoint_sheet,
 };
 use alkanes_support::cellpack::Cellpack;
+use fr_btc_support::{deposit_attestation_message, deposit_script, heartbeat_message, Network};
 #[allow(unused_imports)]
 use metashrew_core::{get_cache, index_pointer::IndexPointer, println, stdio::stdout};
 use ordinals::{Artifact, Runestone};
 use std::fmt::Write;
-use types_support::{deserialize_payments, Payment};
+use types_support::{
+    deserialize_address_params, deserialize_audit_entries, deserialize_contract_info,
+    deserialize_deposits, deserialize_payments, deserialize_redemption_claims, AddressParams,
+    AdminAction, ContractInfo, Deposit, Payment, RedemptionClaim, CONTRACT_INFO_VERSION,
+};
 use wasm_bindgen_test::wasm_bindgen_test;
 
 pub fn simulate_cellpack(height: u64, cellpack: Cellpack) -> Result<(ExtendedCallResponse, u64)> {
-    let parcel = MessageContextParcel {
-        atomic: AtomicPointer::default(),
-        runes: vec![],
-        transaction: Transaction {
+    simulate_cellpack_in(
+        height,
+        cellpack,
+        Transaction {
             version: bitcoin::blockdata::transaction::Version::ONE,
             input: vec![],
             output: vec![],
             lock_time: bitcoin::absolute::LockTime::ZERO,
         },
+    )
+}
+
+/// Simulate a cellpack as if called from `transaction`
+fn simulate_cellpack_in(
+    height: u64,
+    cellpack: Cellpack,
+    transaction: Transaction,
+) -> Result<(ExtendedCallResponse, u64)> {
+    let parcel = MessageContextParcel {
+        atomic: AtomicPointer::default(),
+        runes: vec![],
+        transaction,
         block: create_block_with_coinbase_tx(height as u32),
         height,
         pointer: 0,
@@ -71,7 +93,25 @@ pub fn simulate_cellpack(height: u64, cellpack: Cellpack) -> Result<(ExtendedCal
     simulate_parcel(&parcel, u64::MAX)
 }
 
-fn setup_fr_btc() -> Result<Block> {
+/// Deploy and initialize fr-btc and set the test signer, returning the outpoint
+/// holding the owner auth token
+fn setup_fr_btc() -> Result<OutPoint> {
+    setup_fr_btc_with_signer(Some(signer_pubkey()))
+}
+
+/// Deploy and initialize fr-btc, setting `signer` if given, returning the
+/// outpoint holding the owner auth token
+fn setup_fr_btc_with_signer(signer: Option<XOnlyPublicKey>) -> Result<OutPoint> {
+    setup_asset(fr_btc_build::get_bytes(), Network::Regtest, signer)
+}
+
+/// Deploy a synthetic asset and initialize it for `network`, setting `signer`
+/// if given, returning the outpoint holding the owner auth token
+pub fn setup_asset(
+    asset: Vec<u8>,
+    network: Network,
+    signer: Option<XOnlyPublicKey>,
+) -> Result<OutPoint> {
     let block_height = 880_000;
     let cellpacks: Vec<Cellpack> = [
         //auth token factory init
@@ -84,28 +124,148 @@ fn setup_fr_btc() -> Result<Block> {
         },
         Cellpack {
             target: AlkaneId { block: 3, tx: 0 },
-            inputs: vec![0, 1],
+            inputs: vec![0, network as u128],
         },
     ]
     .into();
-    let test_block = alkane_helpers::init_with_multiple_cellpacks_with_tx(
-        [
-            alkanes_std_auth_token_build::get_bytes(),
-            fr_btc_build::get_bytes(),
-        ]
-        .into(),
+    let mut test_block = alkane_helpers::init_with_multiple_cellpacks_with_tx(
+        [alkanes_std_auth_token_build::get_bytes(), asset].into(),
         cellpacks,
     );
+    // Only mainnet has a default signer, so the owner sets one in the same block
+    if let Some(signer) = signer {
+        let mut set_signer_tx = fr_btc_call_tx(
+            vec![1, 1],
+            &[OutPoint {
+                txid: test_block.txdata.last().unwrap().compute_txid(),
+                vout: 0,
+            }],
+        );
+        set_signer_tx.output.insert(1, signer_key_output(&signer));
+        test_block.txdata.push(set_signer_tx);
+    }
     index_block(&test_block, block_height)?;
     let sheet = get_last_outpoint_sheet(&test_block)?;
-    let auth_token = ProtoruneRuneId { block: 2, tx: 1 };
-    // assert_eq!(sheet.get(&auth_token), 5);
-    Ok(test_block)
+    let info = get_info(block_height)?;
+    assert_eq!(sheet.get(&info.auth_token.into()), 1);
+    assert_eq!(
+        info.signer,
+        signer
+            .map(|key| key.serialize().to_vec())
+            .unwrap_or_default()
+    );
+    Ok(OutPoint {
+        txid: test_block.txdata.last().unwrap().compute_txid(),
+        vout: 0,
+    })
+}
+
+/// An output carrying a signer key for `SetSigner`
+pub fn signer_key_output(key: &XOnlyPublicKey) -> TxOut {
+    TxOut {
+        value: Amount::ZERO,
+        script_pubkey: ScriptBuf::new_op_return(key.serialize()),
+    }
+}
+
+pub fn get_info(height: u64) -> Result<ContractInfo> {
+    let (response, _) = simulate_cellpack(
+        height,
+        Cellpack {
+            target: AlkaneId { block: 4, tx: 0 },
+            inputs: vec![119],
+        },
+    )?;
+    deserialize_contract_info(&response.data)
+}
+
+/// Create a standalone auth token, returning its id and the outpoint holding it
+fn deploy_auth_token(height: u32) -> Result<(AlkaneId, OutPoint)> {
+    let mut block = create_block_with_coinbase_tx(height);
+    let tx = alkane_helpers::create_multiple_cellpack_with_witness_and_in(
+        Witness::default(),
+        vec![Cellpack {
+            target: AlkaneId {
+                block: 6,
+                tx: AUTH_TOKEN_FACTORY_ID,
+            },
+            inputs: vec![0, 1],
+        }],
+        OutPoint::default(),
+        false,
+    );
+    block.txdata.push(tx.clone());
+    index_block(&block, height)?;
+
+    let sheet = get_last_outpoint_sheet(&block)?;
+    let token = sheet
+        .balances()
+        .keys()
+        .next()
+        .cloned()
+        .ok_or(anyhow!("no auth token minted"))?;
+    Ok((
+        AlkaneId {
+            block: token.block,
+            tx: token.tx,
+        },
+        OutPoint {
+            txid: tx.compute_txid(),
+            vout: 0,
+        },
+    ))
+}
+
+/// Secret key of the signer set by `setup_fr_btc`
+pub fn signer_secret_key() -> secp256k1::SecretKey {
+    secp256k1::SecretKey::from_slice(&[0x11; 32]).unwrap()
+}
+
+/// X-only pubkey of the signer set by `setup_fr_btc`
+pub fn signer_pubkey() -> XOnlyPublicKey {
+    signer_secret_key().x_only_public_key(&Secp256k1::new()).0
+}
+
+/// Script pubkey of the signer's P2TR output
+pub fn signer_script() -> ScriptBuf {
+    let secp = Secp256k1::new();
+    let (tweaked_signer_pubkey, _) = signer_pubkey().tap_tweak(&secp, None);
+    ScriptBuf::new_p2tr_tweaked(tweaked_signer_pubkey)
+}
+
+/// Pay 1 BTC to `script` from the coinbase of `height`, without a protostone
+pub fn plain_payment(script_pubkey: ScriptBuf, height: u32) -> Transaction {
+    Transaction {
+        version: Version::ONE,
+        lock_time: bitcoin::absolute::LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint {
+                txid: create_block_with_coinbase_tx(height).txdata[0].compute_txid(),
+                vout: 0,
+            },
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::default(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(100_000_000),
+            script_pubkey,
+        }],
+    }
 }
 
 pub fn create_alkane_tx_frbtc_signer_script(
     cellpacks: Vec<Cellpack>,
     previous_output: OutPoint,
+) -> Transaction {
+    create_wrap_tx(cellpacks, previous_output, signer_script())
+}
+
+/// Build a transaction sending 1 coin to `signer_script` with the given protostones
+pub fn create_wrap_tx(
+    cellpacks: Vec<Cellpack>,
+    previous_output: OutPoint,
+    signer_script: ScriptBuf,
 ) -> Transaction {
     let txins = vec![TxIn {
         previous_output,
@@ -142,51 +302,71 @@ pub fn create_alkane_tx_frbtc_signer_script(
         script_pubkey: runestone,
     };
 
-    // Get the signer pubkey from the contract
-    let signer_pubkey_bytes = [
-        0x07, 0x9a, 0x54, 0xd0, 0xae, 0xf2, 0xb3, 0x43, 0xaa, 0xc8, 0x9c, 0x0f, 0xd7, 0x89, 0xaa,
-        0xb4, 0xac, 0xb9, 0x1f, 0x00, 0xca, 0xa0, 0xf8, 0xd5, 0x15, 0x01, 0x45, 0x2c, 0xe4, 0x7c,
-        0xc9, 0x7d,
-    ]
-    .to_vec();
-    let signer_pubkey = XOnlyPublicKey::from_slice(&signer_pubkey_bytes).unwrap();
-    let secp = Secp256k1::new();
-    let (tweaked_signer_pubkey, _) = signer_pubkey.tap_tweak(&secp, None);
-    let signer_script = ScriptBuf::new_p2tr_tweaked(tweaked_signer_pubkey);
-
     let txout = TxOut {
         value: Amount::from_sat(100_000_000),
         script_pubkey: signer_script,
     };
+    // The minted frBTC goes to output 0, which must not be the signer's
+    let recipient = TxOut {
+        value: Amount::from_sat(546),
+        script_pubkey: get_test_address("user").script_pubkey(),
+    };
     Transaction {
         version: Version::ONE,
         lock_time: bitcoin::absolute::LockTime::ZERO,
         input: txins,
-        output: vec![txout, op_return],
+        output: vec![recipient, txout, op_return],
     }
 }
 
-fn wrap_btc() -> Result<(OutPoint, u64)> {
+/// Send 1 BTC to the signer with a wrap protostone, funded by the coinbase of `height`
+fn send_wrap(height: u32) -> Result<Transaction> {
+    send_wrap_with_witness(height, Witness::default())
+}
+
+/// Like `send_wrap`, with the given witness on the funding input
+fn send_wrap_with_witness(height: u32, witness: Witness) -> Result<Transaction> {
     let fr_btc_id = AlkaneId { block: 4, tx: 0 };
-    let mut block = create_block_with_coinbase_tx(880_001);
+    let mut block = create_block_with_coinbase_tx(height);
     let funding_outpoint = OutPoint {
         txid: block.txdata[0].compute_txid(),
         vout: 0,
     };
-    let wrap_tx = create_alkane_tx_frbtc_signer_script(
+    let mut wrap_tx = create_alkane_tx_frbtc_signer_script(
         vec![Cellpack {
             target: fr_btc_id.clone(),
             inputs: vec![77],
         }],
         funding_outpoint,
     );
+    wrap_tx.input[0].witness = witness;
 
     // Create a block and index it
     block.txdata.push(wrap_tx.clone());
-    index_block(&block, 880_001)?;
+    index_block(&block, height)?;
+    Ok(wrap_tx)
+}
 
-    let sheet = get_last_outpoint_sheet(&block)?;
-    let balance = sheet.get(&fr_btc_id.clone().into());
+pub fn fr_btc_balance(outpoint: &OutPoint) -> Result<u128> {
+    let sheet = load_sheet(
+        &RuneTable::for_protocol(AlkaneMessageContext::protocol_tag())
+            .OUTPOINT_TO_RUNES
+            .select(&consensus_encode(outpoint)?),
+    );
+    Ok(sheet.get(&AlkaneId { block: 4, tx: 0 }.into()))
+}
+
+fn wrap_btc() -> Result<(OutPoint, u64)> {
+    wrap_btc_at(880_001)
+}
+
+fn wrap_btc_at(height: u32) -> Result<(OutPoint, u64)> {
+    let wrap_tx = send_wrap(height)?;
+
+    let balance = fr_btc_balance(&OutPoint {
+        txid: wrap_tx.compute_txid(),
+        vout: 0,
+    })?;
 
     let expected_frbtc_amt = 99500000;
 
@@ -200,23 +380,10 @@ fn wrap_btc() -> Result<(OutPoint, u64)> {
     Ok((wrap_outpoint, expected_frbtc_amt as u64))
 }
 
-fn unwrap_btc(
-    fr_btc_input_outpoint: OutPoint,
-    amount_frbtc: u64,
-    desired_vout: u128,
-    height: u32,
-) -> Result<()> {
+fn unwrap_btc(fr_btc_input_outpoint: OutPoint, amount_frbtc: u64, height: u32) -> Result<()> {
     let fr_btc_id = AlkaneId { block: 4, tx: 0 };
     let mut block = create_block_with_coinbase_tx(height);
-    let unwrap_tx = alkane_helpers::create_multiple_cellpack_with_witness_and_in(
-        Witness::default(),
-        vec![Cellpack {
-            target: fr_btc_id.clone(),
-            inputs: vec![78, desired_vout],
-        }],
-        fr_btc_input_outpoint,
-        false,
-    );
+    let unwrap_tx = unwrap_call_tx(&[fr_btc_input_outpoint], signer_script());
 
     // Create a block and index it
     block.txdata.push(unwrap_tx.clone());
@@ -246,7 +413,7 @@ fn unwrap_btc(
             },
             spendable: OutPoint {
                 txid: unwrap_tx.compute_txid(),
-                vout: desired_vout.try_into()?,
+                vout: 1,
             },
         }
     );
@@ -275,6 +442,177 @@ fn set_signer(input_outpoint: OutPoint, signer_vout: u128) -> Result<Transaction
     Ok(set_signer)
 }
 
+pub fn call_fr_btc(
+    inputs: Vec<u128>,
+    input_outpoint: OutPoint,
+    height: u32,
+) -> Result<Transaction> {
+    call_fr_btc_spending(inputs, &[input_outpoint], height)
+}
+
+/// Call fr-btc from a transaction spending every outpoint, sending in all of their alkanes
+fn call_fr_btc_spending(
+    inputs: Vec<u128>,
+    input_outpoints: &[OutPoint],
+    height: u32,
+) -> Result<Transaction> {
+    index_tx(fr_btc_call_tx(inputs, input_outpoints), height)
+}
+
+/// Build a call to fr-btc spending every outpoint, without indexing it
+pub fn fr_btc_call_tx(inputs: Vec<u128>, input_outpoints: &[OutPoint]) -> Transaction {
+    let fr_btc_id = AlkaneId { block: 4, tx: 0 };
+    let mut tx = alkane_helpers::create_multiple_cellpack_with_witness_and_in(
+        Witness::default(),
+        vec![Cellpack {
+            target: fr_btc_id.clone(),
+            inputs,
+        }],
+        input_outpoints[0],
+        false,
+    );
+    tx.input
+        .extend(input_outpoints[1..].iter().map(|previous_output| TxIn {
+            previous_output: *previous_output,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::default(),
+        }));
+    tx
+}
+
+/// Build an unwrap spending every outpoint, paying out to output 0 and
+/// leaving the signer output 1 to spend when it pays
+pub fn unwrap_call_tx(input_outpoints: &[OutPoint], signer_script: ScriptBuf) -> Transaction {
+    let mut tx = fr_btc_call_tx(vec![78, 1], input_outpoints);
+    tx.output.insert(
+        1,
+        TxOut {
+            value: Amount::from_sat(546),
+            script_pubkey: signer_script,
+        },
+    );
+    tx
+}
+
+/// Index a block at `height` holding the transaction after its coinbase
+pub fn index_tx(tx: Transaction, height: u32) -> Result<Transaction> {
+    let mut block = create_block_with_coinbase_tx(height);
+    block.txdata.push(tx.clone());
+    index_block(&block, height)?;
+    Ok(tx)
+}
+
+/// Read a view returning a list of u128s
+fn u128_view(height: u64, opcode: u128) -> Result<Vec<u128>> {
+    let (response, _) = simulate_cellpack(
+        height,
+        Cellpack {
+            target: AlkaneId { block: 4, tx: 0 },
+            inputs: vec![opcode],
+        },
+    )?;
+    Ok(response
+        .data
+        .chunks(16)
+        .map(|chunk| u128::from_le_bytes(chunk.try_into().unwrap()))
+        .collect())
+}
+
+fn rate_limits(height: u64) -> Result<Vec<u128>> {
+    u128_view(height, 109)
+}
+
+fn signer_liveness(height: u64) -> Result<Vec<u128>> {
+    u128_view(height, 112)
+}
+
+fn delinquency(height: u64) -> Result<Vec<u128>> {
+    u128_view(height, 113)
+}
+
+/// Acknowledge a payment from a transaction spending its outpoint and
+/// paying `value` to its script, with the fulfiller token held at `auth`
+fn fulfill_payment(
+    payment: &Payment,
+    value: u64,
+    auth: OutPoint,
+    height: u32,
+) -> Result<Transaction> {
+    let mut tx = fr_btc_call_tx(vec![17], &[payment.spendable, auth]);
+    tx.output[0] = TxOut {
+        script_pubkey: payment.output.script_pubkey.clone(),
+        value: Amount::from_sat(value),
+    };
+    index_tx(tx, height)
+}
+
+/// Sign a message, packed little-endian into four u128 call inputs
+fn signature_inputs(secret_key: &secp256k1::SecretKey, message: &secp256k1::Message) -> Vec<u128> {
+    let secp = Secp256k1::new();
+    let keypair = secp256k1::Keypair::from_secret_key(&secp, secret_key);
+    secp.sign_schnorr_no_aux_rand(message, &keypair)
+        .serialize()
+        .chunks(16)
+        .map(|chunk| u128::from_le_bytes(chunk.try_into().unwrap()))
+        .collect()
+}
+
+/// Sign a heartbeat for a call spending `prevout` first, as the call's inputs
+fn heartbeat_inputs(secret_key: &secp256k1::SecretKey, prevout: &OutPoint) -> Vec<u128> {
+    [
+        vec![15],
+        signature_inputs(secret_key, &heartbeat_message(prevout)),
+    ]
+    .concat()
+}
+
+/// Attest a deposit to `recipient`'s deposit address, as the call's inputs
+fn attest_deposit_inputs(
+    secret_key: &secp256k1::SecretKey,
+    outpoint: &OutPoint,
+    value: u64,
+    recipient: &Script,
+) -> Vec<u128> {
+    let txid = outpoint.txid.to_byte_array();
+    [
+        vec![
+            28,
+            u128::from_le_bytes(txid[0..16].try_into().unwrap()),
+            u128::from_le_bytes(txid[16..32].try_into().unwrap()),
+            outpoint.vout as u128,
+            value as u128,
+        ],
+        signature_inputs(
+            secret_key,
+            &deposit_attestation_message(outpoint, value, recipient),
+        ),
+    ]
+    .concat()
+}
+
+pub fn deposits(height: u64) -> Result<Vec<Deposit>> {
+    let (response, _) = simulate_cellpack(
+        height,
+        Cellpack {
+            target: AlkaneId { block: 4, tx: 0 },
+            inputs: vec![117, 0, 10],
+        },
+    )?;
+    deserialize_deposits(&response.data[16..].to_vec())
+}
+
+pub fn pending_payments(height: u64) -> Result<Vec<Payment>> {
+    let (response, _) = simulate_cellpack(
+        height,
+        Cellpack {
+            target: AlkaneId { block: 4, tx: 0 },
+            inputs: vec![101],
+        },
+    )?;
+    deserialize_payments(&response.data)
+}
+
 #[wasm_bindgen_test]
 fn test_fr_btc() -> Result<()> {
     clear();
@@ -290,12 +628,159 @@ fn test_fr_btc_wrap_correct_signer() -> Result<()> {
     Ok(())
 }
 
+#[wasm_bindgen_test]
+fn test_fr_btc_get_info() -> Result<()> {
+    clear();
+    setup_fr_btc()?;
+    wrap_btc()?;
+    let (response, _) = simulate_cellpack(
+        880_001,
+        Cellpack {
+            target: AlkaneId { block: 4, tx: 0 },
+            inputs: vec![119],
+        },
+    )?;
+    let info = deserialize_contract_info(&response.data)?;
+    assert_eq!(info.version, CONTRACT_INFO_VERSION);
+    assert_eq!(info.decimals, 8);
+    assert_eq!(info.total_supply, 99500000);
+    assert_eq!(info.premium, 500_000);
+    assert_eq!(info.paused, [false, false, false]);
+    assert_eq!(info.pending_payments, 0);
+    assert_eq!(info.reserves, 100_000_000);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_fr_btc_total_supply_at() -> Result<()> {
+    clear();
+    setup_fr_btc()?;
+    wrap_btc()?;
+    for (height, expected) in [(880_000, 0), (880_001, 99500000), (880_005, 99500000)] {
+        let (response, _) = simulate_cellpack(
+            880_005,
+            Cellpack {
+                target: AlkaneId { block: 4, tx: 0 },
+                inputs: vec![120, height],
+            },
+        )?;
+        assert_eq!(response.data, (expected as u128).to_le_bytes().to_vec());
+    }
+    Ok(())
+}
+
 #[wasm_bindgen_test]
 fn test_fr_btc_unwrap() -> Result<()> {
     clear();
     setup_fr_btc()?;
     let (wrap_out, amt) = wrap_btc()?;
-    unwrap_btc(wrap_out, amt, 0, 880_002)
+    unwrap_btc(wrap_out, amt, 880_002)
+}
+
+#[wasm_bindgen_test]
+fn test_fr_btc_stats() -> Result<()> {
+    clear();
+    setup_fr_btc()?;
+    let (wrap_out, amt) = wrap_btc()?;
+    unwrap_btc(wrap_out, amt, 880_002)?;
+    let (response, _) = simulate_cellpack(
+        880_002,
+        Cellpack {
+            target: AlkaneId { block: 4, tx: 0 },
+            inputs: vec![122],
+        },
+    )?;
+    let stats: Vec<u128> = response
+        .data
+        .chunks(16)
+        .map(|chunk| u128::from_le_bytes(chunk.try_into().unwrap()))
+        .collect();
+    assert_eq!(stats, vec![1, 1, 100_000_000, 99500000, 500000, 1]);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_fr_btc_quote_matches_wrap() -> Result<()> {
+    clear();
+    setup_fr_btc()?;
+    let (response, _) = simulate_cellpack(
+        880_001,
+        Cellpack {
+            target: AlkaneId { block: 4, tx: 0 },
+            inputs: vec![123, 100_000_000],
+        },
+    )?;
+    // wrap_btc asserts the same amount is actually minted, at once
+    assert_eq!(
+        response.data,
+        [99500000u128.to_le_bytes(), 880_001u128.to_le_bytes()].concat()
+    );
+    wrap_btc()?;
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_fr_btc_quote_unwrap_rejects_dust() -> Result<()> {
+    clear();
+    setup_fr_btc()?;
+    let quote = |amount: u128| {
+        simulate_cellpack(
+            880_001,
+            Cellpack {
+                target: AlkaneId { block: 4, tx: 0 },
+                inputs: vec![124, amount],
+            },
+        )
+    };
+    // 546 sats is the P2PKH dust limit, the highest of the standard outputs
+    assert!(quote(545).is_err());
+    let (response, _) = quote(546)?;
+    assert_eq!(response.data, 546u128.to_le_bytes().to_vec());
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_fr_btc_initialize_rejects_network_zero() -> Result<()> {
+    clear();
+    let test_block = alkane_helpers::init_with_multiple_cellpacks_with_tx(
+        [
+            alkanes_std_auth_token_build::get_bytes(),
+            fr_btc_build::get_bytes(),
+        ]
+        .into(),
+        vec![
+            Cellpack {
+                target: AlkaneId {
+                    block: 3,
+                    tx: AUTH_TOKEN_FACTORY_ID,
+                },
+                inputs: vec![100],
+            },
+            Cellpack {
+                target: AlkaneId { block: 3, tx: 0 },
+                inputs: vec![0, 0],
+            },
+        ],
+    );
+    index_block(&test_block, 880_000)?;
+    // The initialization reverts, so no owner auth token is minted
+    assert!(get_last_outpoint_sheet(&test_block)?.balances().is_empty());
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_fr_btc_get_network() -> Result<()> {
+    clear();
+    setup_fr_btc()?;
+    let (response, _) = simulate_cellpack(
+        880_001,
+        Cellpack {
+            target: AlkaneId { block: 4, tx: 0 },
+            inputs: vec![125],
+        },
+    )?;
+    assert_eq!(response.data, vec![Network::Regtest as u8]);
+    Ok(())
 }
 
 #[wasm_bindgen_test]
@@ -312,10 +797,57 @@ fn test_set_signer_no_auth() -> Result<()> {
 }
 
 #[wasm_bindgen_test]
-fn test_fr_btc_wrap_incorrect_signer() -> Result<()> {
+fn test_fr_btc_wrap_requires_signer() -> Result<()> {
     clear();
-    setup_fr_btc()?;
-    let fr_btc_id = AlkaneId { block: 4, tx: 0 };
+    let owner = setup_fr_btc_with_signer(None)?;
+    let wrap_tx = send_wrap(880_001)?;
+    assert_revert_context(
+        &OutPoint {
+            txid: wrap_tx.compute_txid(),
+            vout: 4,
+        },
+        "signer not set",
+    )?;
+
+    // A signer output that is not an OP_RETURN key is rejected
+    let mut set_signer_tx = fr_btc_call_tx(vec![1, 1], &[owner]);
+    set_signer_tx.output.insert(
+        1,
+        TxOut {
+            value: Amount::from_sat(546),
+            script_pubkey: signer_script(),
+        },
+    );
+    let set_signer_tx = index_tx(set_signer_tx, 880_002)?;
+    assert_revert_context(
+        &OutPoint {
+            txid: set_signer_tx.compute_txid(),
+            vout: 4,
+        },
+        "signer output must be an OP_RETURN carrying the key",
+    )?;
+
+    // On revert the owner token is refunded to the first output
+    let mut set_signer_tx = fr_btc_call_tx(
+        vec![1, 1],
+        &[OutPoint {
+            txid: set_signer_tx.compute_txid(),
+            vout: 0,
+        }],
+    );
+    set_signer_tx
+        .output
+        .insert(1, signer_key_output(&signer_pubkey()));
+    index_tx(set_signer_tx, 880_003)?;
+    wrap_btc_at(880_004)?;
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_fr_btc_wrap_incorrect_signer() -> Result<()> {
+    clear();
+    setup_fr_btc()?;
+    let fr_btc_id = AlkaneId { block: 4, tx: 0 };
     let mut block = create_block_with_coinbase_tx(880_001);
     let funding_outpoint = OutPoint {
         txid: block.txdata[0].compute_txid(),
@@ -343,3 +875,1072 @@ fn test_fr_btc_wrap_incorrect_signer() -> Result<()> {
 
     Ok(())
 }
+
+#[wasm_bindgen_test]
+fn test_pause_blocks_wraps() -> Result<()> {
+    clear();
+    let owner = setup_fr_btc()?;
+    let (wrap_out, amt) = wrap_btc()?;
+
+    let pause_tx = call_fr_btc(vec![6, 1, 0, 0], OutPoint::default(), 880_002)?;
+    let outpoint = OutPoint {
+        txid: pause_tx.compute_txid(),
+        vout: 3,
+    };
+    assert_revert_context(&outpoint, "Auth token is not in incoming alkanes")?;
+
+    let pause_tx = call_fr_btc(vec![6, 1, 0, 0], owner, 880_003)?;
+    let owner = OutPoint {
+        txid: pause_tx.compute_txid(),
+        vout: 0,
+    };
+    let (response, _) = simulate_cellpack(
+        880_003,
+        Cellpack {
+            target: AlkaneId { block: 4, tx: 0 },
+            inputs: vec![106],
+        },
+    )?;
+    assert_eq!(response.data, vec![1, 0, 0]);
+
+    let wrap_tx = send_wrap(880_004)?;
+    let outpoint = OutPoint {
+        txid: wrap_tx.compute_txid(),
+        vout: 4,
+    };
+    assert_revert_context(&outpoint, "wrap operations are paused")?;
+
+    // Only wraps were paused, so earlier frBTC can still be unwrapped
+    unwrap_btc(wrap_out, amt, 880_005)?;
+
+    call_fr_btc(vec![7, 1, 0, 0], owner, 880_006)?;
+    wrap_btc_at(880_007)?;
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_granted_roles_act() -> Result<()> {
+    clear();
+    let owner = setup_fr_btc()?;
+    let owner_token = get_info(880_000)?.auth_token;
+    let (token, token_outpoint) = deploy_auth_token(880_001)?;
+
+    let guardian_tx = call_fr_btc(vec![5, token.block, token.tx], owner, 880_002)?;
+    let owner = OutPoint {
+        txid: guardian_tx.compute_txid(),
+        vout: 0,
+    };
+    let grant_tx = call_fr_btc(vec![27, 2, token.block, token.tx], owner, 880_003)?;
+    let owner = OutPoint {
+        txid: grant_tx.compute_txid(),
+        vout: 0,
+    };
+
+    // Ungranted roles are reported as held by the owner's auth token
+    let (response, _) = simulate_cellpack(
+        880_003,
+        Cellpack {
+            target: AlkaneId { block: 4, tx: 0 },
+            inputs: vec![107],
+        },
+    )?;
+    let holders: Vec<(u128, AlkaneId)> = response
+        .data
+        .chunks(48)
+        .map(|chunk| {
+            let field =
+                |i: usize| u128::from_le_bytes(chunk[i * 16..i * 16 + 16].try_into().unwrap());
+            (
+                field(0),
+                AlkaneId {
+                    block: field(1),
+                    tx: field(2),
+                },
+            )
+        })
+        .collect();
+    assert_eq!(holders[1], (1, owner_token.clone()));
+    assert_eq!(holders[2], (2, token.clone()));
+    assert_eq!(holders[3], (3, token.clone()));
+
+    // The fee-admin token sets the premium without the owner token
+    let premium_tx = call_fr_btc(vec![4, 0], token_outpoint, 880_004)?;
+    let token_outpoint = OutPoint {
+        txid: premium_tx.compute_txid(),
+        vout: 0,
+    };
+    assert_eq!(get_info(880_004)?.premium, 0);
+
+    // The guardian token pauses, but only the owner can unpause
+    let pause_tx = call_fr_btc(vec![6, 1, 1, 1], token_outpoint, 880_005)?;
+    let token_outpoint = OutPoint {
+        txid: pause_tx.compute_txid(),
+        vout: 0,
+    };
+    assert_eq!(get_info(880_005)?.paused, [true, true, true]);
+    let unpause_tx = call_fr_btc(vec![7, 1, 1, 1], token_outpoint, 880_006)?;
+    let outpoint = OutPoint {
+        txid: unpause_tx.compute_txid(),
+        vout: 3,
+    };
+    assert_revert_context(&outpoint, "Auth token is not in incoming alkanes")?;
+    let token_outpoint = OutPoint {
+        txid: unpause_tx.compute_txid(),
+        vout: 0,
+    };
+    let unpause_tx = call_fr_btc(vec![7, 1, 1, 1], owner, 880_007)?;
+    let owner = OutPoint {
+        txid: unpause_tx.compute_txid(),
+        vout: 0,
+    };
+    assert_eq!(get_info(880_007)?.paused, [false, false, false]);
+
+    // Revoking the fee-admin role leaves the premium to the owner
+    call_fr_btc(vec![8, 2], owner, 880_008)?;
+    let premium_tx = call_fr_btc(vec![4, 100], token_outpoint, 880_009)?;
+    let outpoint = OutPoint {
+        txid: premium_tx.compute_txid(),
+        vout: 3,
+    };
+    assert_revert_context(&outpoint, "Auth token is not in incoming alkanes")?;
+    assert_eq!(get_info(880_009)?.premium, 0);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_migrate_auth_token() -> Result<()> {
+    clear();
+    let owner = setup_fr_btc()?;
+    let (new_token, new_token_outpoint) = deploy_auth_token(880_001)?;
+
+    // The new token does not own the contract yet
+    let cap_tx = call_fr_btc(vec![10, 50_000_000], new_token_outpoint, 880_002)?;
+    let outpoint = OutPoint {
+        txid: cap_tx.compute_txid(),
+        vout: 3,
+    };
+    assert_revert_context(&outpoint, "Auth token is not in incoming alkanes")?;
+    let new_token_outpoint = OutPoint {
+        txid: cap_tx.compute_txid(),
+        vout: 0,
+    };
+
+    // Ownership cannot move to a token that is not sent in
+    let migrate_tx = call_fr_btc(vec![9, new_token.block, new_token.tx], owner, 880_003)?;
+    let outpoint = OutPoint {
+        txid: migrate_tx.compute_txid(),
+        vout: 3,
+    };
+    assert_revert_context(&outpoint, "new auth token is not in incoming alkanes")?;
+    let owner = OutPoint {
+        txid: migrate_tx.compute_txid(),
+        vout: 0,
+    };
+
+    let migrate_tx = call_fr_btc_spending(
+        vec![9, new_token.block, new_token.tx],
+        &[owner, new_token_outpoint],
+        880_004,
+    )?;
+    assert_eq!(get_info(880_004)?.auth_token, new_token);
+
+    // Both tokens are returned to output 0, where the new one now passes as owner
+    let holder = OutPoint {
+        txid: migrate_tx.compute_txid(),
+        vout: 0,
+    };
+    call_fr_btc(vec![10, 50_000_000], holder, 880_005)?;
+    let (response, _) = simulate_cellpack(
+        880_005,
+        Cellpack {
+            target: AlkaneId { block: 4, tx: 0 },
+            inputs: vec![108],
+        },
+    )?;
+    assert_eq!(response.data, 50_000_000u128.to_le_bytes().to_vec());
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_supply_cap_clamps_and_refunds() -> Result<()> {
+    clear();
+    let owner = setup_fr_btc()?;
+    call_fr_btc(vec![10, 50_000_000], owner, 880_001)?;
+
+    // 1 BTC would mint 99500000 after the premium, but only 50000000 fits
+    let wrap_tx = send_wrap(880_002)?;
+    let wrap_out = OutPoint {
+        txid: wrap_tx.compute_txid(),
+        vout: 0,
+    };
+    assert_eq!(fr_btc_balance(&wrap_out)?, 50_000_000);
+    assert_eq!(get_info(880_002)?.total_supply, 50_000_000);
+
+    // The BTC backing the other 49500000 goes back to the refund output
+    assert_eq!(
+        pending_payments(880_002)?,
+        vec![Payment {
+            output: TxOut {
+                script_pubkey: wrap_tx.output[0].script_pubkey.clone(),
+                value: Amount::from_sat(100_000_000 * 49_500_000 / 99_500_000),
+            },
+            spendable: OutPoint {
+                txid: wrap_tx.compute_txid(),
+                vout: 1,
+            },
+        }]
+    );
+
+    // Unwrapping burns the supply, which frees room under the cap again
+    unwrap_btc(wrap_out, 50_000_000, 880_003)?;
+    assert_eq!(get_info(880_003)?.total_supply, 0);
+    let wrap_tx = send_wrap(880_004)?;
+    let wrap_out = OutPoint {
+        txid: wrap_tx.compute_txid(),
+        vout: 0,
+    };
+    assert_eq!(fr_btc_balance(&wrap_out)?, 50_000_000);
+    Ok(())
+}
+
+/// Get the address parameters in effect
+fn address_params(height: u64) -> Result<AddressParams> {
+    let (response, _) = simulate_cellpack(
+        height,
+        Cellpack {
+            target: AlkaneId { block: 4, tx: 0 },
+            inputs: vec![126],
+        },
+    )?;
+    deserialize_address_params(&response.data)
+}
+
+#[wasm_bindgen_test]
+fn test_owner_sets_and_restores_network_params() -> Result<()> {
+    clear();
+    let owner = setup_fr_btc()?;
+    let signet_prefix = u128::from_le_bytes(*b"tb\0\0\0\0\0\0\0\0\0\0\0\0\0\0");
+
+    // Without the owner token the params are unchanged
+    let params_tx = call_fr_btc(
+        vec![26, signet_prefix, 0x6f, 0xc4],
+        OutPoint::default(),
+        880_001,
+    )?;
+    assert_revert_context(
+        &OutPoint {
+            txid: params_tx.compute_txid(),
+            vout: 3,
+        },
+        "Auth token is not in incoming alkanes",
+    )?;
+    assert_eq!(address_params(880_001)?.bech32_prefix, "bcrt");
+
+    // The owner switches a regtest instance to signet address params
+    let params_tx = call_fr_btc(vec![26, signet_prefix, 0x6f, 0xc4], owner, 880_002)?;
+    let params = address_params(880_002)?;
+    assert_eq!(params.bech32_prefix, "tb");
+    assert_eq!((params.p2pkh_prefix, params.p2sh_prefix), (0x6f, 0xc4));
+
+    // Setting a zero bech32 prefix restores the network's own params
+    let owner = OutPoint {
+        txid: params_tx.compute_txid(),
+        vout: 0,
+    };
+    call_fr_btc(vec![26, 0, 0, 0], owner, 880_003)?;
+    let params = address_params(880_003)?;
+    assert_eq!(params.bech32_prefix, "bcrt");
+    assert_eq!((params.p2pkh_prefix, params.p2sh_prefix), (0x64, 0xc4));
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_unwrap_limit_queues_and_releases() -> Result<()> {
+    clear();
+    let owner = setup_fr_btc()?;
+    call_fr_btc(vec![11, 10, 0, 100_000_000], owner, 880_001)?;
+    let (first, amt) = wrap_btc_at(880_002)?;
+    let (second, _) = wrap_btc_at(880_003)?;
+
+    unwrap_btc(first, amt, 880_004)?;
+    // A second unwrap within 10 blocks would exceed the limit, so it waits
+    let unwrap_tx = index_tx(unwrap_call_tx(&[second], signer_script()), 880_005)?;
+    assert!(pending_payments(880_005)?.is_empty());
+    assert_eq!(
+        rate_limits(880_005)?,
+        vec![10, 0, 100_000_000, 200_000_000, 99_500_000, 1]
+    );
+
+    // The first unwrap is still inside the sliding window
+    call_fr_btc(vec![12], OutPoint::default(), 880_013)?;
+    assert!(pending_payments(880_013)?.is_empty());
+    assert_eq!(rate_limits(880_013)?[5], 1);
+
+    // Ten blocks after the first unwrap the queued one is released
+    call_fr_btc(vec![12], OutPoint::default(), 880_014)?;
+    assert_eq!(
+        pending_payments(880_014)?,
+        vec![Payment {
+            output: TxOut {
+                script_pubkey: unwrap_tx.output[0].script_pubkey.clone(),
+                value: Amount::from_sat(amt),
+            },
+            spendable: OutPoint {
+                txid: unwrap_tx.compute_txid(),
+                vout: 1,
+            },
+        }]
+    );
+    assert_eq!(
+        rate_limits(880_014)?,
+        vec![10, 0, 100_000_000, 0, 99_500_000, 0]
+    );
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_screening_refunds_blocked_sender() -> Result<()> {
+    clear();
+    let owner = setup_fr_btc()?;
+    let secp = Secp256k1::new();
+    let blocked_key =
+        secp256k1::PublicKey::from_secret_key(&secp, &secp256k1::SecretKey::from_slice(&[7; 32])?);
+    let allowed_key =
+        secp256k1::PublicKey::from_secret_key(&secp, &secp256k1::SecretKey::from_slice(&[8; 32])?);
+    let p2wpkh_witness = |key: &secp256k1::PublicKey| {
+        Witness::from_slice(&[vec![0x30; 71], key.serialize().to_vec()])
+    };
+
+    // The owner blocks the sender's P2WPKH script, carried by an extra output
+    let mut block_tx = fr_btc_call_tx(vec![13, 2, 1], &[owner]);
+    block_tx.output.push(TxOut {
+        value: Amount::from_sat(546),
+        script_pubkey: ScriptBuf::new_p2wpkh(&CompressedPublicKey(blocked_key).wpubkey_hash()),
+    });
+    let block_tx = index_tx(block_tx, 880_001)?;
+    let owner = OutPoint {
+        txid: block_tx.compute_txid(),
+        vout: 0,
+    };
+    call_fr_btc(vec![14, 1], owner, 880_002)?;
+
+    // Quotes screen the inputs of the quoting transaction
+    let quote_from = |key: &secp256k1::PublicKey| {
+        let mut tx = fr_btc_call_tx(vec![123, 100_000_000], &[OutPoint::default()]);
+        tx.input[0].witness = p2wpkh_witness(key);
+        simulate_cellpack_in(
+            880_003,
+            Cellpack {
+                target: AlkaneId { block: 4, tx: 0 },
+                inputs: vec![123, 100_000_000],
+            },
+            tx,
+        )
+    };
+    assert_eq!(quote_from(&blocked_key)?.0.data[0..16], 0u128.to_le_bytes());
+    assert_eq!(
+        quote_from(&allowed_key)?.0.data[0..16],
+        99_500_000u128.to_le_bytes()
+    );
+
+    // A wrap from the blocked sender mints nothing and refunds the BTC
+    let wrap_tx = send_wrap_with_witness(880_003, p2wpkh_witness(&blocked_key))?;
+    let wrap_outpoint = OutPoint {
+        txid: wrap_tx.compute_txid(),
+        vout: 0,
+    };
+    assert_eq!(fr_btc_balance(&wrap_outpoint)?, 0);
+    assert_eq!(
+        pending_payments(880_003)?,
+        vec![Payment {
+            output: TxOut {
+                script_pubkey: wrap_tx.output[0].script_pubkey.clone(),
+                value: Amount::from_sat(100_000_000),
+            },
+            spendable: OutPoint {
+                txid: wrap_tx.compute_txid(),
+                vout: 1,
+            },
+        }]
+    );
+
+    // An input that does not reveal its script is refunded too
+    let wrap_tx = send_wrap(880_004)?;
+    let wrap_outpoint = OutPoint {
+        txid: wrap_tx.compute_txid(),
+        vout: 0,
+    };
+    assert_eq!(fr_btc_balance(&wrap_outpoint)?, 0);
+    assert_eq!(pending_payments(880_004)?.len(), 1);
+
+    // Senders that are not blocked still mint
+    let wrap_tx = send_wrap_with_witness(880_005, p2wpkh_witness(&allowed_key))?;
+    let wrap_outpoint = OutPoint {
+        txid: wrap_tx.compute_txid(),
+        vout: 0,
+    };
+    assert_eq!(fr_btc_balance(&wrap_outpoint)?, 99_500_000);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_audit_log_records_initialize() -> Result<()> {
+    clear();
+    setup_fr_btc()?;
+    let (response, _) = simulate_cellpack(
+        880_001,
+        Cellpack {
+            target: AlkaneId { block: 4, tx: 0 },
+            inputs: vec![111, 0, 10],
+        },
+    )?;
+    assert_eq!(u128::from_le_bytes(response.data[0..16].try_into()?), 2);
+    let entries = deserialize_audit_entries(&response.data[16..].to_vec())?;
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].action, AdminAction::Initialize);
+    assert_eq!(entries[0].height, 880_000);
+    assert_eq!(entries[1].action, AdminAction::SetSigner);
+    assert_eq!(entries[1].new_value, signer_pubkey().serialize().to_vec());
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_stale_heartbeat_suspends_wraps() -> Result<()> {
+    clear();
+    let owner = setup_fr_btc()?;
+    // Without a threshold the signer is always considered live
+    assert_eq!(signer_liveness(880_000)?, vec![0, 0, 1]);
+
+    // The threshold counts from the height it is set at
+    call_fr_btc(vec![16, 5], owner, 880_001)?;
+    assert_eq!(signer_liveness(880_001)?, vec![0, 5, 1]);
+    wrap_btc_at(880_006)?;
+
+    let wrap_tx = send_wrap(880_007)?;
+    let outpoint = OutPoint {
+        txid: wrap_tx.compute_txid(),
+        vout: 4,
+    };
+    assert_revert_context(&outpoint, "signer heartbeat is stale, wraps are suspended")?;
+    assert_eq!(signer_liveness(880_007)?, vec![0, 5, 0]);
+
+    // Only the signer key can send a heartbeat
+    let forger = secp256k1::SecretKey::from_slice(&[2; 32])?;
+    let forged_tx = call_fr_btc(
+        heartbeat_inputs(&forger, &OutPoint::default()),
+        OutPoint::default(),
+        880_008,
+    )?;
+    let outpoint = OutPoint {
+        txid: forged_tx.compute_txid(),
+        vout: 3,
+    };
+    assert_revert_context(&outpoint, "heartbeat is not signed by the signer")?;
+
+    call_fr_btc(
+        heartbeat_inputs(&signer_secret_key(), &OutPoint::default()),
+        OutPoint::default(),
+        880_009,
+    )?;
+    assert_eq!(signer_liveness(880_009)?, vec![880_009, 5, 1]);
+    wrap_btc_at(880_010)?;
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_unwrap_records_outstanding_payment() -> Result<()> {
+    clear();
+    setup_fr_btc()?;
+    let (wrap_out, amt) = wrap_btc()?;
+    unwrap_btc(wrap_out, amt, 880_002)?;
+
+    // No SLA configured, so the payment is outstanding but never overdue
+    assert_eq!(delinquency(880_002)?, vec![0, 0, 1, 0, 0]);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_unwrap_spendable_must_pay_the_signer() -> Result<()> {
+    clear();
+    setup_fr_btc()?;
+    let (wrap_out, amt) = wrap_btc()?;
+
+    // A payment spendable from the user's own output could never be fulfilled
+    let unwrap_tx = call_fr_btc(vec![78, 0], wrap_out, 880_002)?;
+    let outpoint = OutPoint {
+        txid: unwrap_tx.compute_txid(),
+        vout: 3,
+    };
+    assert_revert_context(&outpoint, "unwrap output 0 must pay the signer")?;
+    assert!(pending_payments(880_002)?.is_empty());
+
+    let refunded = OutPoint {
+        txid: unwrap_tx.compute_txid(),
+        vout: 0,
+    };
+    unwrap_btc(refunded, amt, 880_003)
+}
+
+#[wasm_bindgen_test]
+fn test_payments_overdue_until_fulfilled() -> Result<()> {
+    clear();
+    let owner = setup_fr_btc()?;
+    let sla_tx = call_fr_btc(vec![18, 3, 0], owner, 880_001)?;
+    let owner = OutPoint {
+        txid: sla_tx.compute_txid(),
+        vout: 0,
+    };
+    let limits_tx = call_fr_btc(vec![11, 10, 0, 100_000_000], owner, 880_002)?;
+    let owner = OutPoint {
+        txid: limits_tx.compute_txid(),
+        vout: 0,
+    };
+    let (first, amt) = wrap_btc_at(880_003)?;
+    let (second, _) = wrap_btc_at(880_004)?;
+    unwrap_btc(first, amt, 880_005)?;
+    let payment = pending_payments(880_005)?.remove(0);
+    // The second unwrap waits on the unwrap limit and is not owed yet
+    index_tx(unwrap_call_tx(&[second], signer_script()), 880_006)?;
+
+    let (fulfiller, fulfiller_outpoint) = deploy_auth_token(880_007)?;
+    call_fr_btc(vec![27, 4, fulfiller.block, fulfiller.tx], owner, 880_008)?;
+    assert_eq!(delinquency(880_008)?, vec![0, 0, 1, 3, 0]);
+    assert_eq!(delinquency(880_009)?, vec![1, 880_005, 1, 3, 0]);
+
+    // Only the fulfiller can acknowledge a payment
+    let unauthorized_tx = fulfill_payment(&payment, amt, OutPoint::default(), 880_009)?;
+    let outpoint = OutPoint {
+        txid: unauthorized_tx.compute_txid(),
+        vout: 3,
+    };
+    assert_revert_context(&outpoint, "fulfiller role token is not in incoming alkanes")?;
+
+    // Paying less than the payment does not fulfill it
+    let short_tx = fulfill_payment(&payment, amt - 1, fulfiller_outpoint, 880_010)?;
+    let outpoint = OutPoint {
+        txid: short_tx.compute_txid(),
+        vout: 3,
+    };
+    assert_revert_context(&outpoint, "transaction fulfills no outstanding payment")?;
+    let fulfiller_outpoint = OutPoint {
+        txid: short_tx.compute_txid(),
+        vout: 0,
+    };
+
+    fulfill_payment(&payment, amt, fulfiller_outpoint, 880_011)?;
+    assert_eq!(delinquency(880_011)?, vec![0, 0, 0, 3, 0]);
+
+    // The queued unwrap is overdue counting from its release, not its burn
+    call_fr_btc(vec![12], OutPoint::default(), 880_015)?;
+    assert_eq!(pending_payments(880_015)?.len(), 1);
+    assert_eq!(delinquency(880_018)?, vec![0, 0, 1, 3, 0]);
+    assert_eq!(delinquency(880_019)?, vec![1, 880_015, 1, 3, 0]);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_unwraps_sharing_an_outpoint_are_each_acknowledged() -> Result<()> {
+    clear();
+    let owner = setup_fr_btc()?;
+    let (wrapped, amt) = wrap_btc()?;
+    let half = amt / 2;
+
+    // The first protostone splits the tokens between two unwraps that both
+    // leave their payment spendable from output 1
+    let fr_btc_id = AlkaneId { block: 4, tx: 0 };
+    let unwrap = |edicts: Vec<ProtostoneEdict>, inputs: Vec<u128>| Protostone {
+        message: Cellpack {
+            target: fr_btc_id.clone(),
+            inputs,
+        }
+        .encipher(),
+        pointer: Some(0),
+        refund: Some(0),
+        edicts,
+        from: None,
+        burn: None,
+        protocol_tag: 1,
+    };
+    let split = |output: u128| ProtostoneEdict {
+        id: ProtoruneRuneId { block: 4, tx: 0 },
+        amount: half as u128,
+        output,
+    };
+    let protostones = vec![
+        unwrap(vec![split(5), split(6)], vec![99]),
+        unwrap(vec![], vec![78, 1]),
+        unwrap(vec![], vec![78, 1]),
+    ];
+    let destination = get_test_address("user").script_pubkey();
+    let unwrap_tx = Transaction {
+        version: Version::ONE,
+        lock_time: bitcoin::absolute::LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: wrapped,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::default(),
+        }],
+        output: vec![
+            TxOut {
+                value: Amount::from_sat(546),
+                script_pubkey: destination.clone(),
+            },
+            TxOut {
+                value: Amount::from_sat(546),
+                script_pubkey: signer_script(),
+            },
+            TxOut {
+                value: Amount::from_sat(0),
+                script_pubkey: (Runestone {
+                    etching: None,
+                    pointer: Some(0),
+                    edicts: Vec::new(),
+                    mint: None,
+                    protocol: protostones.encipher().ok(),
+                })
+                .encipher(),
+            },
+        ],
+    };
+    let unwrap_tx = index_tx(unwrap_tx, 880_002)?;
+    let spendable = OutPoint {
+        txid: unwrap_tx.compute_txid(),
+        vout: 1,
+    };
+    let payments = pending_payments(880_002)?;
+    assert_eq!(payments.len(), 2);
+    assert!(payments
+        .iter()
+        .all(|payment| payment.spendable == spendable));
+    assert_eq!(delinquency(880_002)?, vec![0, 0, 2, 0, 0]);
+
+    // One output fulfills only one of the two payments
+    let mut first_tx = fr_btc_call_tx(vec![17], &[spendable, owner]);
+    first_tx.output[0] = TxOut {
+        script_pubkey: destination.clone(),
+        value: Amount::from_sat(half),
+    };
+    let first_tx = index_tx(first_tx, 880_003)?;
+    assert_eq!(delinquency(880_003)?, vec![0, 0, 1, 0, 0]);
+
+    // The remaining payment is still found from the shared outpoint
+    let owner = OutPoint {
+        txid: first_tx.compute_txid(),
+        vout: 0,
+    };
+    let mut second_tx = fr_btc_call_tx(vec![17], &[spendable, owner]);
+    second_tx.output[0] = TxOut {
+        script_pubkey: destination,
+        value: Amount::from_sat(half),
+    };
+    index_tx(second_tx, 880_004)?;
+    assert_eq!(delinquency(880_004)?, vec![0, 0, 0, 0, 0]);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_wind_down_after_missed_payment() -> Result<()> {
+    clear();
+    let owner = setup_fr_btc()?;
+    let (first, amt) = wrap_btc_at(880_001)?;
+    let (second, _) = wrap_btc_at(880_002)?;
+    let redeem_tx = call_fr_btc(vec![20], first, 880_003)?;
+    let outpoint = OutPoint {
+        txid: redeem_tx.compute_txid(),
+        vout: 3,
+    };
+    assert_revert_context(&outpoint, "redemptions are only available during wind-down")?;
+    let first = OutPoint {
+        txid: redeem_tx.compute_txid(),
+        vout: 0,
+    };
+
+    // A signer meeting its obligations cannot be wound down
+    let wind_down_tx = call_fr_btc(vec![19, 0], owner, 880_004)?;
+    let outpoint = OutPoint {
+        txid: wind_down_tx.compute_txid(),
+        vout: 3,
+    };
+    assert_revert_context(
+        &outpoint,
+        "wind-down requires overdue payments or a stale signer heartbeat",
+    )?;
+    let owner = OutPoint {
+        txid: wind_down_tx.compute_txid(),
+        vout: 0,
+    };
+
+    let sla_tx = call_fr_btc(vec![18, 1, 0], owner, 880_005)?;
+    let owner = OutPoint {
+        txid: sla_tx.compute_txid(),
+        vout: 0,
+    };
+    unwrap_btc(second, amt, 880_006)?;
+    assert_eq!(delinquency(880_008)?[0], 1);
+
+    // 2 BTC received, less the 0.995 BTC unwrap still owed
+    let wind_down_tx = call_fr_btc(vec![19, 0], owner, 880_008)?;
+    let (response, _) = simulate_cellpack(
+        880_008,
+        Cellpack {
+            target: AlkaneId { block: 4, tx: 0 },
+            inputs: vec![114],
+        },
+    )?;
+    let state: Vec<u128> = response.data[0..80]
+        .chunks(16)
+        .map(|chunk| u128::from_le_bytes(chunk.try_into().unwrap()))
+        .collect();
+    assert_eq!(state, vec![1, 880_008, 100_500_000, 99_500_000, 0]);
+    assert_eq!(
+        response.data[80..].to_vec(),
+        wind_down_tx.output[0].script_pubkey.as_bytes().to_vec()
+    );
+
+    // The remaining holder redeems the whole of the reserves
+    let redeem_tx = call_fr_btc(vec![20], first, 880_009)?;
+    let (response, _) = simulate_cellpack(
+        880_009,
+        Cellpack {
+            target: AlkaneId { block: 4, tx: 0 },
+            inputs: vec![115, 0, 10],
+        },
+    )?;
+    let claims = deserialize_redemption_claims(&response.data[16..].to_vec())?;
+    assert_eq!(
+        claims,
+        vec![RedemptionClaim {
+            claimant: redeem_tx.output[0].script_pubkey.clone(),
+            burned: amt as u128,
+            amount: 100_500_000,
+            height: 880_009,
+            txid: redeem_tx.compute_txid(),
+        }]
+    );
+    assert_eq!(get_info(880_009)?.total_supply, 0);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_delayed_mint_claimed_after_delay() -> Result<()> {
+    clear();
+    let owner = setup_fr_btc()?;
+    call_fr_btc(vec![21, 50_000_000, 3], owner, 880_001)?;
+    let (response, _) = simulate_cellpack(
+        880_002,
+        Cellpack {
+            target: AlkaneId { block: 4, tx: 0 },
+            inputs: vec![123, 100_000_000],
+        },
+    )?;
+    assert_eq!(
+        response.data,
+        [99_500_000u128.to_le_bytes(), 880_005u128.to_le_bytes()].concat()
+    );
+
+    // A wrap above the threshold mints nothing until the delay has passed
+    let wrap_tx = send_wrap(880_002)?;
+    let recipient = OutPoint {
+        txid: wrap_tx.compute_txid(),
+        vout: 0,
+    };
+    assert_eq!(fr_btc_balance(&recipient)?, 0);
+    assert_eq!(u128_view(880_002, 116)?, vec![50_000_000, 3, 99_500_000]);
+    assert_eq!(get_info(880_002)?.total_supply, 0);
+
+    // Spending the recipient output once the wrap has matured claims the mint
+    let claim_tx = call_fr_btc(vec![22], recipient, 880_005)?;
+    let claimed = OutPoint {
+        txid: claim_tx.compute_txid(),
+        vout: 0,
+    };
+    assert_eq!(fr_btc_balance(&claimed)?, 99_500_000);
+    assert_eq!(u128_view(880_005, 116)?, vec![50_000_000, 3, 0]);
+    assert_eq!(get_info(880_005)?.total_supply, 99_500_000);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_fr_btc_wrap_outputs_claimed_once() -> Result<()> {
+    clear();
+    setup_fr_btc()?;
+    let fr_btc_id = AlkaneId { block: 4, tx: 0 };
+    let mut block = create_block_with_coinbase_tx(880_001);
+    let funding_outpoint = OutPoint {
+        txid: block.txdata[0].compute_txid(),
+        vout: 0,
+    };
+    // The first protostone claims output 1 explicitly, so the second has nothing left
+    let wrap_tx = create_alkane_tx_frbtc_signer_script(
+        vec![
+            Cellpack {
+                target: fr_btc_id.clone(),
+                inputs: vec![79, 2],
+            },
+            Cellpack {
+                target: fr_btc_id.clone(),
+                inputs: vec![79, 2],
+            },
+        ],
+        funding_outpoint,
+    );
+    block.txdata.push(wrap_tx.clone());
+    index_block(&block, 880_001)?;
+
+    let sheet = get_last_outpoint_sheet(&block)?;
+    assert_eq!(sheet.get(&fr_btc_id.clone().into()), 99500000);
+
+    let outpoint = OutPoint {
+        txid: wrap_tx.compute_txid(),
+        vout: 5,
+    };
+    assert_revert_context(&outpoint, "already processed")?;
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_fr_btc_wrap_skips_claimed_outputs() -> Result<()> {
+    clear();
+    setup_fr_btc()?;
+    let fr_btc_id = AlkaneId { block: 4, tx: 0 };
+    let mut block = create_block_with_coinbase_tx(880_001);
+    let funding_outpoint = OutPoint {
+        txid: block.txdata[0].compute_txid(),
+        vout: 0,
+    };
+    // Output 1 is claimed explicitly, then a plain wrap takes the signer output left over
+    let mut wrap_tx = create_alkane_tx_frbtc_signer_script(
+        vec![
+            Cellpack {
+                target: fr_btc_id.clone(),
+                inputs: vec![79, 2],
+            },
+            Cellpack {
+                target: fr_btc_id.clone(),
+                inputs: vec![77],
+            },
+        ],
+        funding_outpoint,
+    );
+    wrap_tx.output.push(wrap_tx.output[1].clone());
+    block.txdata.push(wrap_tx.clone());
+    index_block(&block, 880_001)?;
+
+    let sheet = get_last_outpoint_sheet(&block)?;
+    assert_eq!(sheet.get(&fr_btc_id.clone().into()), 2 * 99500000);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_fr_btc_wrap_to_signer_output() -> Result<()> {
+    clear();
+    setup_fr_btc()?;
+    let fr_btc_id = AlkaneId { block: 4, tx: 0 };
+    let mut block = create_block_with_coinbase_tx(880_001);
+    let funding_outpoint = OutPoint {
+        txid: block.txdata[0].compute_txid(),
+        vout: 0,
+    };
+    // Swap the outputs so the protostone pointer lands on the signer
+    let mut wrap_tx = create_alkane_tx_frbtc_signer_script(
+        vec![Cellpack {
+            target: fr_btc_id.clone(),
+            inputs: vec![77],
+        }],
+        funding_outpoint,
+    );
+    wrap_tx.output.swap(0, 1);
+    block.txdata.push(wrap_tx.clone());
+    index_block(&block, 880_001)?;
+
+    let outpoint = OutPoint {
+        txid: wrap_tx.compute_txid(),
+        vout: 4,
+    };
+    assert_revert_context(&outpoint, "transfers cannot target a signer output")?;
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_fr_btc_deposit_address_recorded_and_claimed() -> Result<()> {
+    clear();
+    setup_fr_btc()?;
+    let register_tx = call_fr_btc(vec![25], OutPoint::default(), 880_001)?;
+    let recipient = register_tx.output[0].script_pubkey.clone();
+    let deposit_address = deposit_script(&signer_pubkey(), &recipient)?;
+
+    // The keeper records the block's deposits before a wrap paying the same address
+    let mut block = create_block_with_coinbase_tx(880_002);
+    block
+        .txdata
+        .push(fr_btc_call_tx(vec![23], &[OutPoint::default()]));
+    let mut wrap_tx = fr_btc_call_tx(vec![77], &[OutPoint::default()]);
+    wrap_tx.output.push(TxOut {
+        value: Amount::from_sat(100_000_000),
+        script_pubkey: deposit_address,
+    });
+    block.txdata.push(wrap_tx.clone());
+    index_block(&block, 880_002)?;
+
+    let recorded = deposits(880_002)?;
+    assert_eq!(recorded.len(), 1);
+    assert_eq!(
+        recorded[0].outpoint,
+        OutPoint {
+            txid: wrap_tx.compute_txid(),
+            vout: 2,
+        }
+    );
+    assert_eq!(recorded[0].recipient, Some(recipient.clone()));
+
+    // The wrap skips the recorded output instead of reverting
+    assert_eq!(u128_view(880_002, 122)?[0], 1);
+    let wrapped = OutPoint {
+        txid: wrap_tx.compute_txid(),
+        vout: 0,
+    };
+    assert_eq!(fr_btc_balance(&wrapped)?, 0);
+
+    let claim_tx = call_fr_btc(vec![24, 0], OutPoint::default(), 880_003)?;
+    let claimed = OutPoint {
+        txid: claim_tx.compute_txid(),
+        vout: 0,
+    };
+    assert_eq!(fr_btc_balance(&claimed)?, 99_500_000);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_fr_btc_attested_deposit_claimed_by_recipient() -> Result<()> {
+    clear();
+    setup_fr_btc()?;
+    let register_tx = call_fr_btc(vec![25], OutPoint::default(), 880_001)?;
+    let recipient = register_tx.output[0].script_pubkey.clone();
+    let deposit_address = deposit_script(&signer_pubkey(), &recipient)?;
+
+    // A plain payment to the signer that reveals no funding script can never
+    // be claimed, so it is not recorded
+    let mut block = create_block_with_coinbase_tx(880_002);
+    block.txdata.push(plain_payment(signer_script(), 880_002));
+    block
+        .txdata
+        .push(fr_btc_call_tx(vec![23], &[OutPoint::default()]));
+    index_block(&block, 880_002)?;
+    assert!(deposits(880_002)?.is_empty());
+
+    // No keeper records this payment to the deposit address in its own block
+    let deposit_tx = index_tx(plain_payment(deposit_address, 880_003), 880_003)?;
+    let deposit_outpoint = OutPoint {
+        txid: deposit_tx.compute_txid(),
+        vout: 0,
+    };
+
+    let forger = secp256k1::SecretKey::from_slice(&[2; 32])?;
+    let forged_tx = call_fr_btc(
+        attest_deposit_inputs(&forger, &deposit_outpoint, 100_000_000, &recipient),
+        OutPoint::default(),
+        880_004,
+    )?;
+    let outpoint = OutPoint {
+        txid: forged_tx.compute_txid(),
+        vout: 3,
+    };
+    assert_revert_context(&outpoint, "deposit is not attested by the signer")?;
+
+    call_fr_btc(
+        attest_deposit_inputs(
+            &signer_secret_key(),
+            &deposit_outpoint,
+            100_000_000,
+            &recipient,
+        ),
+        OutPoint::default(),
+        880_005,
+    )?;
+    let recorded = deposits(880_005)?;
+    assert_eq!(recorded.len(), 1);
+    assert_eq!(recorded[0].outpoint, deposit_outpoint);
+    assert_eq!(recorded[0].value, 100_000_000);
+    assert_eq!(recorded[0].recipient, Some(recipient.clone()));
+
+    // Only the committed recipient can receive the mint
+    let mut thief_tx = fr_btc_call_tx(vec![24, 0], &[OutPoint::default()]);
+    let thief = secp256k1::PublicKey::from_secret_key(&Secp256k1::new(), &forger);
+    thief_tx.output[0].script_pubkey =
+        ScriptBuf::new_p2wpkh(&CompressedPublicKey(thief).wpubkey_hash());
+    let thief_tx = index_tx(thief_tx, 880_006)?;
+    let outpoint = OutPoint {
+        txid: thief_tx.compute_txid(),
+        vout: 3,
+    };
+    assert_revert_context(
+        &outpoint,
+        "deposit must be claimed to its committed recipient",
+    )?;
+
+    let claim_tx = call_fr_btc(vec![24, 0], OutPoint::default(), 880_007)?;
+    assert_eq!(claim_tx.output[0].script_pubkey, recipient);
+    let claimed = OutPoint {
+        txid: claim_tx.compute_txid(),
+        vout: 0,
+    };
+    assert_eq!(fr_btc_balance(&claimed)?, 99_500_000);
+    assert!(deposits(880_007)?[0].claimed);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_fr_btc_plain_deposit_claimed_by_funding_script() -> Result<()> {
+    clear();
+    setup_fr_btc()?;
+    let depositor = secp256k1::PublicKey::from_secret_key(
+        &Secp256k1::new(),
+        &secp256k1::SecretKey::from_slice(&[9; 32])?,
+    );
+    let witness = Witness::from_slice(&[vec![0x30; 71], depositor.serialize().to_vec()]);
+    let funding_script = ScriptBuf::new_p2wpkh(&CompressedPublicKey(depositor).wpubkey_hash());
+
+    let mut deposit_tx = plain_payment(signer_script(), 880_001);
+    deposit_tx.input[0].witness = witness.clone();
+    let mut block = create_block_with_coinbase_tx(880_001);
+    block.txdata.push(deposit_tx.clone());
+    block
+        .txdata
+        .push(fr_btc_call_tx(vec![23], &[OutPoint::default()]));
+    index_block(&block, 880_001)?;
+
+    let recorded = deposits(880_001)?;
+    assert_eq!(recorded.len(), 1);
+    assert_eq!(
+        recorded[0].outpoint,
+        OutPoint {
+            txid: deposit_tx.compute_txid(),
+            vout: 0,
+        }
+    );
+    assert_eq!(recorded[0].recipient, None);
+    assert_eq!(recorded[0].funding_scripts, vec![funding_script]);
+
+    // Claiming requires spending from a script that funded the deposit
+    let thief_tx = call_fr_btc(vec![24, 0], OutPoint::default(), 880_002)?;
+    let outpoint = OutPoint {
+        txid: thief_tx.compute_txid(),
+        vout: 3,
+    };
+    assert_revert_context(
+        &outpoint,
+        "claim must spend from a script that funded the deposit",
+    )?;
+
+    let mut claim_tx = fr_btc_call_tx(vec![24, 0], &[OutPoint::default()]);
+    claim_tx.input[0].witness = witness;
+    let claim_tx = index_tx(claim_tx, 880_003)?;
+    let claimed = OutPoint {
+        txid: claim_tx.compute_txid(),
+        vout: 0,
+    };
+    assert_eq!(fr_btc_balance(&claimed)?, 99_500_000);
+    assert!(deposits(880_003)?[0].claimed);
+    Ok(())
+}
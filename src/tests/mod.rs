@@ -2,3 +2,4 @@ pub mod helpers;
 //pub mod integration;
 pub mod std;
 pub mod fr_btc;
+pub mod synthetic;
@@ -0,0 +1,188 @@
+use crate::tests::fr_btc::{
+    call_fr_btc, create_wrap_tx, deposits, fr_btc_balance, fr_btc_call_tx, get_info, index_tx,
+    pending_payments, plain_payment, setup_asset, signer_pubkey, signer_script, simulate_cellpack,
+    unwrap_call_tx,
+};
+use crate::tests::std::{fr_bel_build, fr_doge_build, fr_fb_build, fr_lky_build};
+use alkanes::indexer::index_block;
+use alkanes::tests::helpers::clear;
+use alkanes_support::cellpack::Cellpack;
+use alkanes_support::id::AlkaneId;
+use anyhow::Result;
+use bitcoin::key::Parity;
+use bitcoin::secp256k1::{Secp256k1, SecretKey, XOnlyPublicKey};
+use bitcoin::{OutPoint, PublicKey, ScriptBuf};
+use fr_btc_support::{deposit_key, Network};
+use protorune::test_helpers::create_block_with_coinbase_tx;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// P2PKH script of an x-only key lifted to its even-parity full key, as the
+/// P2PKH assets lock coins
+fn p2pkh_script(key: &XOnlyPublicKey) -> ScriptBuf {
+    ScriptBuf::new_p2pkh(&PublicKey::new(key.public_key(Parity::Even)).pubkey_hash())
+}
+
+/// Wrap 1 coin by paying `signer_script`, returning the outpoint holding the minted tokens
+fn wrap_to(signer_script: ScriptBuf, height: u32) -> Result<OutPoint> {
+    let wrap_tx = index_tx(
+        create_wrap_tx(
+            vec![Cellpack {
+                target: AlkaneId { block: 4, tx: 0 },
+                inputs: vec![77],
+            }],
+            OutPoint::default(),
+            signer_script,
+        ),
+        height,
+    )?;
+    Ok(OutPoint {
+        txid: wrap_tx.compute_txid(),
+        vout: 0,
+    })
+}
+
+/// Initialize an asset for `network` and check its metadata, its signer
+/// script and that a wrap to that script mints
+fn check_asset(
+    asset: Vec<u8>,
+    network: Network,
+    name: &str,
+    symbol: &str,
+    expected_signer_script: ScriptBuf,
+) -> Result<()> {
+    clear();
+    setup_asset(asset, network, Some(signer_pubkey()))?;
+    let info = get_info(880_001)?;
+    assert_eq!(info.name, name);
+    assert_eq!(info.symbol, symbol);
+    assert_eq!(info.decimals, 8);
+    assert_eq!(info.signer_script, expected_signer_script);
+    let (response, _) = simulate_cellpack(
+        880_001,
+        Cellpack {
+            target: AlkaneId { block: 4, tx: 0 },
+            inputs: vec![125],
+        },
+    )?;
+    assert_eq!(response.data, vec![network as u8]);
+
+    let wrapped = wrap_to(expected_signer_script, 880_001)?;
+    assert_eq!(fr_btc_balance(&wrapped)?, 99_500_000);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_fr_doge_metadata_and_signer_script() -> Result<()> {
+    check_asset(
+        fr_doge_build::get_bytes(),
+        Network::Dogecoin,
+        "SUBFROST DOGE",
+        "frDOGE",
+        p2pkh_script(&signer_pubkey()),
+    )
+}
+
+#[wasm_bindgen_test]
+fn test_fr_lky_metadata_and_signer_script() -> Result<()> {
+    check_asset(
+        fr_lky_build::get_bytes(),
+        Network::Luckycoin,
+        "SUBFROST LKY",
+        "frLKY",
+        p2pkh_script(&signer_pubkey()),
+    )
+}
+
+#[wasm_bindgen_test]
+fn test_fr_bel_metadata_and_signer_script() -> Result<()> {
+    check_asset(
+        fr_bel_build::get_bytes(),
+        Network::Bellscoin,
+        "SUBFROST BEL",
+        "frBEL",
+        p2pkh_script(&signer_pubkey()),
+    )
+}
+
+#[wasm_bindgen_test]
+fn test_fr_fb_metadata_and_signer_script() -> Result<()> {
+    check_asset(
+        fr_fb_build::get_bytes(),
+        Network::Fractal,
+        "SUBFROST FB",
+        "frFB",
+        signer_script(),
+    )
+}
+
+#[wasm_bindgen_test]
+fn test_fr_doge_unwrap_pays_p2pkh() -> Result<()> {
+    clear();
+    setup_asset(
+        fr_doge_build::get_bytes(),
+        Network::Dogecoin,
+        Some(signer_pubkey()),
+    )?;
+    let wrapped = wrap_to(p2pkh_script(&signer_pubkey()), 880_001)?;
+
+    // Quotes and unwraps follow Dogecoin's 0.01 DOGE dust limit
+    let quote = |amount: u128| {
+        simulate_cellpack(
+            880_001,
+            Cellpack {
+                target: AlkaneId { block: 4, tx: 0 },
+                inputs: vec![124, amount],
+            },
+        )
+    };
+    assert!(quote(999_999).is_err());
+    assert!(quote(1_000_000).is_ok());
+
+    let destination = p2pkh_script(
+        &SecretKey::from_slice(&[3; 32])?
+            .x_only_public_key(&Secp256k1::new())
+            .0,
+    );
+    let mut unwrap_tx = unwrap_call_tx(&[wrapped], p2pkh_script(&signer_pubkey()));
+    unwrap_tx.output[0].script_pubkey = destination.clone();
+    index_tx(unwrap_tx, 880_002)?;
+
+    let payments = pending_payments(880_002)?;
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].output.script_pubkey, destination);
+    assert_eq!(payments[0].output.value.to_sat(), 99_500_000);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_fr_doge_deposit_address_claimed() -> Result<()> {
+    clear();
+    setup_asset(
+        fr_doge_build::get_bytes(),
+        Network::Dogecoin,
+        Some(signer_pubkey()),
+    )?;
+    let register_tx = call_fr_btc(vec![25], OutPoint::default(), 880_001)?;
+    let recipient = register_tx.output[0].script_pubkey.clone();
+    // The deposit address is the P2PKH script of the tweaked signer key
+    let deposit_address = p2pkh_script(&deposit_key(&signer_pubkey(), &recipient)?);
+
+    let mut block = create_block_with_coinbase_tx(880_002);
+    block
+        .txdata
+        .push(fr_btc_call_tx(vec![23], &[OutPoint::default()]));
+    block.txdata.push(plain_payment(deposit_address, 880_002));
+    index_block(&block, 880_002)?;
+
+    let recorded = deposits(880_002)?;
+    assert_eq!(recorded.len(), 1);
+    assert_eq!(recorded[0].recipient, Some(recipient));
+
+    let claim_tx = call_fr_btc(vec![24, 0], OutPoint::default(), 880_003)?;
+    let claimed = OutPoint {
+        txid: claim_tx.compute_txid(),
+        vout: 0,
+    };
+    assert_eq!(fr_btc_balance(&claimed)?, 99_500_000);
+    Ok(())
+}